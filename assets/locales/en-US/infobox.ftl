@@ -0,0 +1,20 @@
+# Texts of the animals infobox
+
+infobox-title = Animals
+infobox-cats = Cat stuff
+infobox-dogs = Dog stuff
+infobox-facts = Facts
+infobox-kittens = Kittens
+
+fact-previous = Previous fact
+fact-random = Random fact
+fact-next = Next fact
+
+# Alt texts of the gallery images
+alt-kittens-kittens-png = A fluffy ginger kitten stretching both front paws up in the air
+alt-dog-png = A white, black and tan Jack Russell terrier sitting with its mouth open
+
+# Button prompts of the active input device
+prompt-mouse = *Left click* dogs, *right click* cats, *middle click* hide
+prompt-keyboard = *Tab* focus, *Enter* dogs, *Space* cats
+prompt-gamepad = *RB* dogs, *LB* cats, *A* kittens or facts
@@ -0,0 +1,20 @@
+# Texter i djurrutan
+
+infobox-title = Djur
+infobox-cats = Kattgrejer
+infobox-dogs = Hundgrejer
+infobox-facts = Fakta
+infobox-kittens = Kattungar
+
+fact-previous = Föregående fakta
+fact-random = Slumpad fakta
+fact-next = Nästa fakta
+
+# Alt-texter för bilderna i galleriet
+alt-kittens-kittens-png = En fluffig rödbrun kattunge som sträcker upp båda framtassarna i luften
+alt-dog-png = En vit, svart och brun jack russell-terrier som sitter med öppen mun
+
+# Knappar för den aktiva inmatningsenheten
+prompt-mouse = *Vänsterklick* hundar, *högerklick* katter, *mittenklick* dölj
+prompt-keyboard = *Tab* fokus, *Enter* hundar, *Mellanslag* katter
+prompt-gamepad = *RB* hundar, *LB* katter, *A* kattungar eller fakta
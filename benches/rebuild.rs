@@ -0,0 +1,284 @@
+//! Cost of a state change in a large tree under each approach of `branch`. The root is governed
+//! by the `layout` field of the state and holds hundreds of sections, with a group per content
+//! field in each. A group holds item slots, whose deep subtrees depend on the group's field.
+//!
+//! The added-marker approach rebuilds the whole tree from the root on any change, a level per
+//! frame. The staged one does the same in a single frame. The registry runs in stages too, but
+//! only rebuilds the item slots whose field changed.
+
+use bevy::{
+    ecs::system::{EntityCommands, SystemParamItem},
+    prelude::*,
+};
+use bevy_ui_pattern_example::{
+    branch::{spawn_added_branches, AddUiSchedule, UiBranch, UiSchedule, UiSlotRegistry},
+    builder::{node, slot, text},
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+#[derive(Clone, Copy, Debug)]
+enum Approach {
+    Components,
+    ComponentsSchedule,
+    Resources,
+}
+
+/// Field of the state changed between rebuilds
+#[derive(Clone, Copy, Debug)]
+enum Change {
+    /// Governs the whole tree
+    Layout,
+    /// Governs the items of one group in every section
+    Field,
+}
+
+/// Number of content fields of the state, each governing a group of every section
+const FIELDS: usize = 3;
+
+/// Levels of branches in the tree, the added-marker approach needs an update per level
+const LEVELS: usize = 3;
+
+/// Sections of the tree, items in each group and levels of nodes in each item
+#[derive(Clone, Copy)]
+struct TreeSize {
+    sections: usize,
+    items: usize,
+    depth: usize,
+}
+
+#[derive(Component, Clone, Copy, Default)]
+struct BenchState {
+    layout: usize,
+    fields: [usize; FIELDS],
+}
+
+#[derive(Component)]
+struct BenchRoot;
+#[derive(Component)]
+struct BenchGroup<const F: usize>;
+#[derive(Component)]
+struct BenchItem<const F: usize>;
+
+/// The root, holding a group slot per field in each section
+struct BenchPanel;
+
+impl UiBranch for BenchPanel {
+    type Slot = BenchRoot;
+    type State = BenchState;
+    type Context = Res<'static, TreeSize>;
+
+    fn build(
+        entity: &mut EntityCommands,
+        state: &BenchState,
+        size: &mut SystemParamItem<Self::Context>,
+    ) {
+        let color = [Color::RED, Color::MAROON][state.layout % 2];
+        entity
+            .insert_bundle(node(color, Style::default()))
+            .with_children(|parent| {
+                for _ in 0..size.sections {
+                    parent
+                        .spawn_bundle(node(color, Style::default()))
+                        .with_children(|parent| {
+                            parent.spawn_bundle(slot()).insert(BenchGroup::<0>);
+                            parent.spawn_bundle(slot()).insert(BenchGroup::<1>);
+                            parent.spawn_bundle(slot()).insert(BenchGroup::<2>);
+                        });
+                }
+            });
+    }
+
+    fn changed(old: &BenchState, new: &BenchState) -> bool {
+        old.layout != new.layout
+    }
+}
+
+/// A group of items, rebuilt only along with its section
+struct BenchGroups<const F: usize>;
+
+impl<const F: usize> UiBranch for BenchGroups<F> {
+    type Slot = BenchGroup<F>;
+    type State = BenchState;
+    type Context = Res<'static, TreeSize>;
+
+    fn build(
+        entity: &mut EntityCommands,
+        _state: &BenchState,
+        size: &mut SystemParamItem<Self::Context>,
+    ) {
+        entity
+            .insert_bundle(node(Color::ORANGE, Style::default()))
+            .with_children(|parent| {
+                for _ in 0..size.items {
+                    parent.spawn_bundle(slot()).insert(BenchItem::<F>);
+                }
+            });
+    }
+
+    fn changed(_old: &BenchState, _new: &BenchState) -> bool {
+        false
+    }
+}
+
+/// An item, whose nodes depend on field `F`
+struct BenchItems<const F: usize>;
+
+impl<const F: usize> UiBranch for BenchItems<F> {
+    type Slot = BenchItem<F>;
+    type State = BenchState;
+    type Context = Res<'static, TreeSize>;
+
+    fn build(
+        entity: &mut EntityCommands,
+        state: &BenchState,
+        size: &mut SystemParamItem<Self::Context>,
+    ) {
+        let color = [Color::GREEN, Color::BLUE][state.fields[F] % 2];
+        entity
+            .insert_bundle(node(color, Style::default()))
+            .with_children(|parent| spawn_nodes(parent, color, size.depth));
+    }
+
+    fn changed(old: &BenchState, new: &BenchState) -> bool {
+        old.fields[F] != new.fields[F]
+    }
+}
+
+/// Two nodes per level, with texts as leaves
+fn spawn_nodes(parent: &mut ChildBuilder, color: Color, depth: usize) {
+    for _ in 0..2 {
+        if depth == 0 {
+            parent.spawn_bundle(text(Handle::default(), 16.0, Color::WHITE));
+        } else {
+            parent
+                .spawn_bundle(node(color, Style::default()))
+                .with_children(|parent| spawn_nodes(parent, color, depth - 1));
+        }
+    }
+}
+
+/// Runs the branches in a stage per level with `add`, `add_branch` or `add_registered_branch`
+macro_rules! ui_schedule {
+    ($add:ident) => {{
+        let mut ui_schedule = UiSchedule::default();
+        ui_schedule
+            .$add::<BenchPanel>("Ui-level-1")
+            .$add::<BenchGroups<0>>("Ui-level-2")
+            .$add::<BenchGroups<1>>("Ui-level-2")
+            .$add::<BenchGroups<2>>("Ui-level-2")
+            .$add::<BenchItems<0>>("Ui-level-3")
+            .$add::<BenchItems<1>>("Ui-level-3")
+            .$add::<BenchItems<2>>("Ui-level-3");
+        ui_schedule
+    }};
+}
+
+fn app(approach: Approach, size: TreeSize) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins).insert_resource(size);
+
+    match approach {
+        Approach::Components => {
+            app.add_system(spawn_added_branches::<BenchPanel>)
+                .add_system(spawn_added_branches::<BenchGroups<0>>)
+                .add_system(spawn_added_branches::<BenchGroups<1>>)
+                .add_system(spawn_added_branches::<BenchGroups<2>>)
+                .add_system(spawn_added_branches::<BenchItems<0>>)
+                .add_system(spawn_added_branches::<BenchItems<1>>)
+                .add_system(spawn_added_branches::<BenchItems<2>>);
+        }
+        Approach::ComponentsSchedule => {
+            app.add_ui_schedule(ui_schedule!(add_branch));
+        }
+        Approach::Resources => {
+            app.init_resource::<UiSlotRegistry<BenchState>>()
+                .add_ui_schedule(ui_schedule!(add_registered_branch));
+        }
+    }
+
+    let root = app.world.spawn().insert(BenchRoot).id();
+    if matches!(approach, Approach::Resources) {
+        app.world
+            .resource_mut::<UiSlotRegistry<BenchState>>()
+            .insert_tree(root, BenchState::default());
+    } else {
+        app.world.entity_mut(root).insert(BenchState::default());
+    }
+    for _ in 0..LEVELS {
+        app.update();
+    }
+    app
+}
+
+fn apply_change(state: &mut BenchState, change: Change) {
+    match change {
+        Change::Layout => state.layout += 1,
+        Change::Field => state.fields[1] += 1,
+    }
+}
+
+/// Changes the state and updates until the tree is rebuilt. Every approach gets an update per
+/// level, which the added-marker one needs. The staged ones are done after the first update and
+/// skip the schedule in the others.
+fn change_state(app: &mut App, approach: Approach, change: Change) {
+    match approach {
+        Approach::Resources => {
+            let mut registry = app.world.resource_mut::<UiSlotRegistry<BenchState>>();
+            for (_, state) in registry.states_mut() {
+                apply_change(state, change);
+            }
+        }
+        _ => {
+            let mut states = app.world.query::<&mut BenchState>();
+            for mut state in states.iter_mut(&mut app.world) {
+                apply_change(&mut state, change);
+            }
+        }
+    }
+    for _ in 0..LEVELS {
+        app.update();
+    }
+}
+
+fn rebuild(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rebuild");
+    group.sample_size(10);
+    for size in [
+        TreeSize {
+            sections: 100,
+            items: 4,
+            depth: 3,
+        },
+        TreeSize {
+            sections: 300,
+            items: 4,
+            depth: 3,
+        },
+        TreeSize {
+            sections: 100,
+            items: 4,
+            depth: 5,
+        },
+    ] {
+        for change in [Change::Layout, Change::Field] {
+            for approach in [
+                Approach::Components,
+                Approach::ComponentsSchedule,
+                Approach::Resources,
+            ] {
+                let mut app = app(approach, size);
+                group.bench_function(
+                    BenchmarkId::new(
+                        format!("{:?}/{:?}", approach, change),
+                        format!("{}x{}x{}", size.sections, size.items, size.depth),
+                    ),
+                    |b| b.iter(|| change_state(&mut app, approach, change)),
+                );
+            }
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, rebuild);
+criterion_main!(benches);
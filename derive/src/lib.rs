@@ -0,0 +1,156 @@
+//! `#[derive(UiBranch)]`, see `bevy_ui_pattern_example::branch`
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Ident, Path, Result, Type};
+
+#[proc_macro_derive(UiBranch, attributes(ui_branch))]
+pub fn derive_ui_branch(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Settings of the `#[ui_branch(...)]` attribute on the enum
+#[derive(Default)]
+struct BranchAttributes {
+    slot: Option<Type>,
+    state: Option<Type>,
+    field: Option<Ident>,
+    /// Further fields of the state the content depends on
+    rebuild_on: Vec<Ident>,
+    context: Option<Type>,
+}
+
+impl BranchAttributes {
+    fn parse(input: &DeriveInput) -> Result<Self> {
+        let mut attributes = Self::default();
+        for attribute in input
+            .attrs
+            .iter()
+            .filter(|a| a.path().is_ident("ui_branch"))
+        {
+            attribute.parse_nested_meta(|meta| {
+                let value = meta.value()?;
+                if meta.path.is_ident("slot") {
+                    attributes.slot = Some(value.parse()?);
+                } else if meta.path.is_ident("state") {
+                    attributes.state = Some(value.parse()?);
+                } else if meta.path.is_ident("field") {
+                    attributes.field = Some(value.parse()?);
+                } else if meta.path.is_ident("strategy") {
+                    return Err(meta.error(
+                        "the strategy is picked by the runner the branch is added to, \
+                         see `bevy_ui_pattern_example::branch`",
+                    ));
+                } else if meta.path.is_ident("rebuild_on") {
+                    attributes.rebuild_on.push(value.parse()?);
+                } else if meta.path.is_ident("context") {
+                    attributes.context = Some(value.parse()?);
+                } else {
+                    return Err(meta.error("unknown ui_branch setting"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(attributes)
+    }
+}
+
+/// Error for a missing setting
+fn missing<T>(input: &DeriveInput, setting: &str) -> Result<T> {
+    Err(Error::new_spanned(
+        &input.ident,
+        format!("missing `#[ui_branch({} = ...)]`", setting),
+    ))
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let variants = match &input.data {
+        Data::Enum(data) => &data.variants,
+        _ => {
+            return Err(Error::new_spanned(
+                name,
+                "UiBranch can only be derived for enums",
+            ))
+        }
+    };
+
+    // The build function of every variant
+    let mut arms = Vec::new();
+    for variant in variants {
+        let mut build: Option<Path> = None;
+        for attribute in variant
+            .attrs
+            .iter()
+            .filter(|a| a.path().is_ident("ui_branch"))
+        {
+            attribute.parse_nested_meta(|meta| {
+                if meta.path.is_ident("build") {
+                    build = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown ui_branch setting"))
+                }
+            })?;
+        }
+        let variant_name = &variant.ident;
+        match build {
+            Some(build) => arms.push(quote! {
+                #name::#variant_name { .. } => #build(slot, state, context),
+            }),
+            None => {
+                return Err(Error::new_spanned(
+                    variant,
+                    "missing `#[ui_branch(build = ...)]`",
+                ))
+            }
+        }
+    }
+
+    let attributes = BranchAttributes::parse(input)?;
+    let slot = match &attributes.slot {
+        Some(slot) => slot,
+        None => return missing(input, "slot"),
+    };
+    let state = match &attributes.state {
+        Some(state) => state,
+        None => return missing(input, "state"),
+    };
+    let field = match &attributes.field {
+        Some(field) => field,
+        None => return missing(input, "field"),
+    };
+    let context = match &attributes.context {
+        Some(context) => context,
+        None => return missing(input, "context"),
+    };
+    let fields: Vec<&Ident> = std::iter::once(field)
+        .chain(&attributes.rebuild_on)
+        .collect();
+
+    Ok(quote! {
+        impl ::bevy_ui_pattern_example::branch::UiBranch for #name {
+            type Slot = #slot;
+            type State = #state;
+            type Context = #context;
+
+            fn build(
+                slot: &mut ::bevy::ecs::system::EntityCommands,
+                state: &Self::State,
+                context: &mut ::bevy::ecs::system::SystemParamItem<Self::Context>,
+            ) {
+                match state.#field {
+                    #(#arms)*
+                }
+            }
+
+            fn changed(old: &Self::State, new: &Self::State) -> bool {
+                #(old.#fields != new.#fields)||*
+            }
+        }
+    })
+}
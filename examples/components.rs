@@ -1,319 +1,179 @@
 //! This example illustrates a bevy ecs-ui design pattern
 
-use bevy::prelude::*;
-
-#[derive(PartialEq, Eq)]
-enum UiInfoboxAnimalsState {
-    Dogs,
-    Cats,
-}
-
-#[derive(PartialEq, Eq)]
-enum UiInfoboxCatsContentState {
-    Kittens,
-    Facts,
-}
+use std::marker::PhantomData;
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_ui_pattern_example::{
+    accessibility::{AccessibilityPlugin, AccessibleRole},
+    branch::spawn_added_branches,
+    facts::FactsPlugin,
+    focus::{FocusPlugin, Focusable},
+    gallery::GalleryPlugin,
+    hover::ui_tree_root,
+    infobox::{
+        fact_navigation_system, gamepad_system, mouse_click_system, FrameCounter, UiInfoboxAnimals,
+        UiInfoboxAnimalsState, UiInfoboxCat, UiInfoboxCatsContentState, UiInfoboxPanel,
+        UiInfoboxRoot, UiInfoboxState,
+    },
+    input_device::InputDevicePlugin,
+    locale::LocalePlugin,
+    panel::{PanelGeometry, PanelPlugin},
+    placement::{Anchor, Placement, PlacementPlugin},
+    replay::InputScriptPlugin,
+    responsive::{ResponsivePanel, ResponsivePlugin},
+    scroll::ScrollPlugin,
+};
+
+/// A unit walking around in the world, which one of the infoboxes follows
+#[derive(Component)]
+struct Unit;
 
-struct UiInfoboxState {
-    visibility: bool,
-    animal_state: UiInfoboxAnimalsState,
-    animal_cats_state: UiInfoboxCatsContentState,
+/// Stable handles to the stateful slot `T` of each infobox, keyed by the infobox root.
+/// Kept up to date by `register_ui_slot::<T>` as slots are spawned and despawned.
+struct UiSlots<T: Component> {
+    entities: HashMap<Entity, Entity>,
+    marker: PhantomData<T>,
 }
-impl Default for UiInfoboxState {
+impl<T: Component> Default for UiSlots<T> {
     fn default() -> Self {
         Self {
-            visibility: true,
-            animal_state: UiInfoboxAnimalsState::Cats,
-            animal_cats_state: UiInfoboxCatsContentState::Kittens,
+            entities: HashMap::default(),
+            marker: PhantomData,
         }
     }
 }
-
-#[derive(Component)]
-struct UiInfoboxRoot;
-#[derive(Component)]
-struct UiInfoboxAnimals;
-#[derive(Component)]
-struct UiInfoboxCat;
-
-struct FrameCounter(u32);
+impl<T: Component> UiSlots<T> {
+    fn get(&self, root: Entity) -> Option<Entity> {
+        self.entities.get(&root).copied()
+    }
+}
 
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
-        .insert_resource(UiInfoboxState::default())
+        .add_plugin(LocalePlugin)
+        .add_plugin(ScrollPlugin)
+        .add_plugin(FocusPlugin)
+        .add_plugin(InputDevicePlugin)
+        .add_plugin(PanelPlugin)
+        .add_plugin(ResponsivePlugin)
+        .add_plugin(PlacementPlugin)
+        .add_plugin(AccessibilityPlugin::<UiInfoboxRoot>::default())
+        .add_plugin(FactsPlugin)
+        .add_plugin(GalleryPlugin)
+        .add_plugin(InputScriptPlugin)
         .insert_resource(FrameCounter(0))
+        .init_resource::<UiSlots<UiInfoboxAnimals>>()
+        .init_resource::<UiSlots<UiInfoboxCat>>()
         .add_startup_system(setup)
+        .add_system(move_units)
         .add_system(mouse_click_system)
-        .add_system(spawn_ui_infobox.after(mouse_click_system))
-        .add_system(spawn_ui_infobox_cats.after(spawn_ui_infobox))
-        .add_system(spawn_ui_infobox_dogs.after(spawn_ui_infobox))
-        .add_system(spawn_ui_infobox_cats_facts.after(spawn_ui_infobox_cats))
-        .add_system(spawn_ui_infobox_cats_kittens.after(spawn_ui_infobox_cats))
+        .add_system(gamepad_system)
+        .add_system(fact_navigation_system.before(spawn_added_branches::<UiInfoboxPanel>))
+        .add_system(spawn_added_branches::<UiInfoboxPanel>.after(mouse_click_system))
+        .add_system(
+            spawn_added_branches::<UiInfoboxAnimalsState>
+                .after(spawn_added_branches::<UiInfoboxPanel>),
+        )
+        .add_system(
+            spawn_added_branches::<UiInfoboxCatsContentState>
+                .after(spawn_added_branches::<UiInfoboxAnimalsState>),
+        )
+        // Despawns are only visible once the commands above are applied
+        .add_system_to_stage(CoreStage::PostUpdate, register_ui_slot::<UiInfoboxAnimals>)
+        .add_system_to_stage(CoreStage::PostUpdate, register_ui_slot::<UiInfoboxCat>)
+        .add_system_to_stage(
+            CoreStage::PostUpdate,
+            log_ui_slots
+                .after(register_ui_slot::<UiInfoboxAnimals>)
+                .after(register_ui_slot::<UiInfoboxCat>),
+        )
         .run();
 }
 
 fn setup(mut commands: Commands) {
     commands.spawn_bundle(Camera2dBundle::default());
-}
-
-fn mouse_click_system(
-    mouse_button_input: Res<Input<MouseButton>>,
-    mut ui_infobox_state: ResMut<UiInfoboxState>,
-    mut frame_counter: ResMut<FrameCounter>,
-) {
-    frame_counter.0 += 1;
-
-    if mouse_button_input.just_pressed(MouseButton::Middle) {
-        info!("Toggle visibility");
-        ui_infobox_state.visibility = !ui_infobox_state.visibility;
-        frame_counter.0 = 0;
-    }
-
-    if mouse_button_input.just_pressed(MouseButton::Left) {
-        info!("Will show dogs");
-        ui_infobox_state.animal_state = UiInfoboxAnimalsState::Dogs;
-        frame_counter.0 = 0;
-    }
-
-    if mouse_button_input.just_pressed(MouseButton::Right) {
-        info!("Will show cats");
-        if ui_infobox_state.animal_state == UiInfoboxAnimalsState::Cats {
-            if ui_infobox_state.animal_cats_state == UiInfoboxCatsContentState::Kittens {
-                info!("Will show cats facts");
-                ui_infobox_state.animal_cats_state = UiInfoboxCatsContentState::Facts;
-            } else {
-                info!("Will show cat kittens");
-                ui_infobox_state.animal_cats_state = UiInfoboxCatsContentState::Kittens;
-            }
-        } else {
-            ui_infobox_state.animal_state = UiInfoboxAnimalsState::Cats;
-        }
-        frame_counter.0 = 0;
+    let unit = commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: Color::PURPLE,
+                custom_size: Some(Vec2::splat(40.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(Unit)
+        .id();
+
+    for placement in [
+        Placement::Anchored {
+            anchor: Anchor::TopLeft,
+            offset: Vec2::new(20.0, 10.0),
+        },
+        Placement::Following {
+            entity: unit,
+            anchor: Anchor::BottomCenter,
+            offset: Vec2::new(0.0, -30.0),
+        },
+    ] {
+        commands
+            .spawn()
+            .insert(UiInfoboxRoot)
+            .insert(placement)
+            .insert(PanelGeometry::new(Vec2::ZERO, Vec2::new(600.0, 400.0)))
+            .insert(ResponsivePanel::new(
+                Vec2::new(380.0, 400.0),
+                Vec2::new(600.0, 400.0),
+                Vec2::new(900.0, 600.0),
+            ))
+            .insert(UiInfoboxState::default())
+            .insert(Interaction::None)
+            .insert(Focusable)
+            .insert(AccessibleRole::Panel);
     }
 }
 
-fn spawn_ui_infobox(
-    mut commands: Commands,
-    ui_root: Query<Entity, With<UiInfoboxRoot>>,
-    ui_infobox_state: Res<UiInfoboxState>,
-    asset_server: Res<AssetServer>,
-    frame_counter: Res<FrameCounter>,
-) {
-    if ui_infobox_state.is_changed() {
-        // Reset ui
-        if let Ok(e) = ui_root.get_single() {
-            commands.entity(e).despawn_recursive();
-        }
-        if ui_infobox_state.visibility {
-            info!("Frame: {:?} infobox root", frame_counter.0);
-            //Spawn root node
-            commands
-                .spawn_bundle(NodeBundle {
-                    color: Color::RED.into(),
-                    style: Style {
-                        size: Size::new(Val::Px(600.0), Val::Px(400.0)),
-                        position_type: PositionType::Absolute,
-                        align_items: AlignItems::FlexStart,
-                        flex_direction: FlexDirection::ColumnReverse,
-                        position: UiRect {
-                            left: Val::Px(20.0),
-                            bottom: Val::Px(10.0),
-                            ..default()
-                        },
-                        ..default()
-                    },
-                    ..default()
-                })
-                .insert(Interaction::None)
-                .insert(UiInfoboxRoot)
-                // Add children some of which can be stateful
-                .with_children(|parent| {
-                    parent.spawn_bundle(TextBundle {
-                        text: Text::from_section(
-                            "Animals".to_owned(),
-                            TextStyle {
-                                font_size: 32.0,
-                                color: Color::BLUE,
-                                font: asset_server.load("font.ttf").clone(),
-                            },
-                        ),
-                        style: Style {
-                            margin: UiRect::all(Val::Px(5.0)),
-                            ..default()
-                        },
-                        ..default()
-                    });
-                    parent
-                        .spawn_bundle(NodeBundle::default())
-                        .insert(UiInfoboxAnimals);
-                });
-        }
+fn move_units(time: Res<Time>, mut units: Query<&mut Transform, With<Unit>>) {
+    let angle = time.seconds_since_startup() as f32 * 0.5;
+    for mut transform in units.iter_mut() {
+        transform.translation = Vec3::new(angle.cos() * 300.0, angle.sin() * 150.0, 0.0);
     }
 }
 
-fn spawn_ui_infobox_cats(
-    mut commands: Commands,
-    query: Query<Entity, Added<UiInfoboxAnimals>>,
-    ui_infobox_state: Res<UiInfoboxState>,
-    asset_server: Res<AssetServer>,
+/// Walks up the hierarchy to the infobox root owning `entity`
+fn register_ui_slot<T: Component>(
+    mut slots: ResMut<UiSlots<T>>,
+    added: Query<Entity, Added<T>>,
+    removed: RemovedComponents<T>,
+    parents: Query<&Parent>,
+    roots: Query<(), With<UiInfoboxRoot>>,
 ) {
-    for e in query.iter() {
-        if ui_infobox_state.animal_state == UiInfoboxAnimalsState::Cats {
-            commands
-                .entity(e)
-                .insert_bundle(NodeBundle {
-                    color: Color::ORANGE.into(),
-                    style: Style {
-                        flex_direction: FlexDirection::ColumnReverse,
-                        align_items: AlignItems::FlexStart,
-                        size: Size::new(Val::Percent(100.0), Val::Px(300.0)),
-                        ..default()
-                    },
-                    ..default()
-                })
-                .with_children(|parent| {
-                    //Should spawn cat stuff
-                    parent.spawn_bundle(TextBundle {
-                        text: Text::from_section(
-                            "Cat stuff",
-                            TextStyle {
-                                font_size: 20.0,
-                                color: Color::BLUE,
-                                font: asset_server.load("font.ttf").clone(),
-                            },
-                        ),
-                        style: Style {
-                            margin: UiRect::all(Val::Px(5.0)),
-                            ..default()
-                        },
-                        ..default()
-                    });
-                    parent
-                        .spawn_bundle(NodeBundle::default())
-                        .insert(UiInfoboxCat);
-                });
-        }
+    for e in removed.iter() {
+        slots.entities.retain(|_, slot| *slot != e);
     }
-}
-
-fn spawn_ui_infobox_cats_facts(
-    mut commands: Commands,
-    query: Query<Entity, Added<UiInfoboxCat>>,
-    ui_infobox_state: Res<UiInfoboxState>,
-    asset_server: Res<AssetServer>,
-) {
-    for e in query.iter() {
-        if ui_infobox_state.animal_cats_state == UiInfoboxCatsContentState::Facts {
-            commands
-                .entity(e)
-                .insert_bundle(NodeBundle {
-                    color: Color::GREEN.into(),
-                    style: Style {
-                        flex_direction: FlexDirection::ColumnReverse,
-                        size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
-                        ..default()
-                    },
-                    ..default()
-                })
-                .with_children(|parent| {
-                    //Should spawn cat facts
-                    parent.spawn_bundle(TextBundle {
-                        text: Text::from_section(
-                            "Cats can jump 5 times their own height.",
-                            TextStyle {
-                                font_size: 20.0,
-                                color: Color::BLUE,
-                                font: asset_server.load("font.ttf").clone(),
-                            },
-                        ),
-                        style: Style {
-                            margin: UiRect::all(Val::Px(5.0)),
-                            ..default()
-                        },
-                        ..default()
-                    });
-                });
+    for e in added.iter() {
+        let root = ui_tree_root(e, &parents);
+        if roots.contains(root) {
+            slots.entities.insert(root, e);
         }
     }
 }
 
-fn spawn_ui_infobox_cats_kittens(
-    mut commands: Commands,
-    query: Query<Entity, Added<UiInfoboxCat>>,
-    ui_infobox_state: Res<UiInfoboxState>,
-    asset_server: Res<AssetServer>,
+fn log_ui_slots(
+    ui_roots: Query<Entity, With<UiInfoboxRoot>>,
+    animals: Res<UiSlots<UiInfoboxAnimals>>,
+    cats: Res<UiSlots<UiInfoboxCat>>,
     frame_counter: Res<FrameCounter>,
 ) {
-    for e in query.iter() {
-        if ui_infobox_state.animal_cats_state == UiInfoboxCatsContentState::Kittens {
-            info!("Frame: {:?} infobox kittens", frame_counter.0);
-            commands
-                .entity(e)
-                .insert_bundle(NodeBundle {
-                    color: Color::GREEN.into(),
-                    style: Style {
-                        flex_direction: FlexDirection::ColumnReverse,
-                        size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
-                        ..default()
-                    },
-                    ..default()
-                })
-                .with_children(|parent| {
-                    //Should spawn kittens
-                    parent.spawn_bundle(ImageBundle {
-                        image: asset_server.load("kittens.png").into(),
-                        ..default()
-                    });
-                });
-        }
-    }
-}
-
-///  Dog stuff
-fn spawn_ui_infobox_dogs(
-    mut commands: Commands,
-    query: Query<Entity, Added<UiInfoboxAnimals>>,
-    ui_infobox_state: Res<UiInfoboxState>,
-    asset_server: Res<AssetServer>,
-) {
-    for e in query.iter() {
-        if ui_infobox_state.animal_state == UiInfoboxAnimalsState::Dogs {
-            commands
-                .entity(e)
-                .insert_bundle(NodeBundle {
-                    color: Color::ORANGE.into(),
-                    style: Style {
-                        flex_direction: FlexDirection::ColumnReverse,
-                        align_items: AlignItems::FlexStart,
-                        size: Size::new(Val::Percent(100.0), Val::Px(300.0)),
-                        ..default()
-                    },
-                    ..default()
-                })
-                .with_children(|parent| {
-                    //Should spawn dog stuff
-                    parent.spawn_bundle(TextBundle {
-                        text: Text::from_section(
-                            "Dog stuff",
-                            TextStyle {
-                                font_size: 20.0,
-                                color: Color::YELLOW,
-                                font: asset_server.load("font.ttf").clone(),
-                            },
-                        ),
-                        style: Style {
-                            margin: UiRect::all(Val::Px(5.0)),
-                            ..default()
-                        },
-                        ..default()
-                    });
-
-                    parent.spawn_bundle(ImageBundle {
-                        image: asset_server.load("dog.png").into(),
-                        ..default()
-                    });
-                });
+    if animals.is_changed() || cats.is_changed() {
+        for root in ui_roots.iter() {
+            info!(
+                "Frame: {:?} infobox {:?} slots animals: {:?} cat: {:?}",
+                frame_counter.0,
+                root,
+                animals.get(root),
+                cats.get(root)
+            );
         }
     }
 }
@@ -1,346 +1,124 @@
 //! This example illustrates a bevy ecs-ui design pattern
 
 use bevy::prelude::*;
-
-#[derive(PartialEq, Eq)]
-enum UiInfoboxAnimalsState {
-    Dogs,
-    Cats,
-}
-
-#[derive(PartialEq, Eq)]
-enum UiInfoboxCatsContentState {
-    Kittens,
-    Facts,
-}
-
-struct UiInfoboxState {
-    visibility: bool,
-    animal_state: UiInfoboxAnimalsState,
-    animal_cats_state: UiInfoboxCatsContentState,
-}
-impl Default for UiInfoboxState {
-    fn default() -> Self {
-        Self {
-            visibility: true,
-            animal_state: UiInfoboxAnimalsState::Cats,
-            animal_cats_state: UiInfoboxCatsContentState::Kittens,
-        }
-    }
-}
-
-#[derive(Component)]
-struct UiInfoboxRoot;
+use bevy_ui_pattern_example::{
+    accessibility::{AccessibilityPlugin, AccessibleRole},
+    branch::{AddUiSchedule, UiSchedule, UiScheduleMetrics},
+    facts::FactsPlugin,
+    focus::{FocusPlugin, Focusable},
+    gallery::GalleryPlugin,
+    infobox::{
+        fact_navigation_system, gamepad_system, mouse_click_system, FrameCounter,
+        UiInfoboxAnimalsState, UiInfoboxCatsContentState, UiInfoboxPanel, UiInfoboxRoot,
+        UiInfoboxState,
+    },
+    input_device::InputDevicePlugin,
+    locale::LocalePlugin,
+    panel::{PanelGeometry, PanelPlugin},
+    placement::{Anchor, Placement, PlacementPlugin},
+    replay::InputScriptPlugin,
+    responsive::{ResponsivePanel, ResponsivePlugin},
+    scroll::ScrollPlugin,
+};
+
+/// A unit walking around in the world, which one of the infoboxes follows
 #[derive(Component)]
-struct UiInfoboxAnimals;
-#[derive(Component)]
-struct UiInfoboxCat;
-
-struct UiSchedule(Schedule);
-
-struct FrameCounter(u32);
+struct Unit;
 
 fn main() {
-    let mut ui_schedule = Schedule::default();
-    ui_schedule.add_stage(
-        "Ui-level-1",
-        SystemStage::parallel().with_system_set(SystemSet::new().with_system(spawn_ui_infobox)),
-    );
-    ui_schedule.add_stage(
-        "Ui-level-2",
-        SystemStage::parallel().with_system_set(
-            SystemSet::new()
-                .with_system(spawn_ui_infobox_cats)
-                .with_system(spawn_ui_infobox_dogs),
-        ),
-    );
-    ui_schedule.add_stage(
-        "Ui-level-3",
-        SystemStage::parallel().with_system_set(
-            SystemSet::new()
-                .with_system(spawn_ui_infobox_cats_facts)
-                .with_system(spawn_ui_infobox_cats_kittens),
-        ),
-    );
+    let mut ui_schedule = UiSchedule::default();
+    ui_schedule
+        .add_branch::<UiInfoboxPanel>("Ui-level-1")
+        .add_branch::<UiInfoboxAnimalsState>("Ui-level-2")
+        .add_branch::<UiInfoboxCatsContentState>("Ui-level-3")
+        // Content nested deeper than the stages still settles within the frame
+        .until_settled(4);
 
     App::new()
         .add_plugins(DefaultPlugins)
-        .insert_resource(UiInfoboxState::default())
-        .insert_resource(UiSchedule(ui_schedule))
+        .add_plugin(LocalePlugin)
+        .add_plugin(ScrollPlugin)
+        .add_plugin(FocusPlugin)
+        .add_plugin(InputDevicePlugin)
+        .add_plugin(PanelPlugin)
+        .add_plugin(ResponsivePlugin)
+        .add_plugin(PlacementPlugin)
+        .add_plugin(AccessibilityPlugin::<UiInfoboxRoot>::default())
+        .add_plugin(FactsPlugin)
+        .add_plugin(GalleryPlugin)
+        .add_plugin(InputScriptPlugin)
+        .add_ui_schedule(ui_schedule)
         .insert_resource(FrameCounter(0))
         .add_startup_system(setup)
+        .add_system(move_units)
         .add_system(mouse_click_system)
-        .add_system(run_ui_schedule.exclusive_system())
+        .add_system(gamepad_system)
+        .add_system(fact_navigation_system)
+        .add_system(log_ui_schedule_metrics)
         .run();
 }
 
-fn run_ui_schedule(world: &mut World) {
-    world.resource_scope(|mut world, mut schedule: Mut<UiSchedule>| {
-        schedule.0.run_once(&mut world);
-    });
-}
-
 fn setup(mut commands: Commands) {
     commands.spawn_bundle(Camera2dBundle::default());
-}
-
-fn mouse_click_system(
-    mouse_button_input: Res<Input<MouseButton>>,
-    mut ui_infobox_state: ResMut<UiInfoboxState>,
-    mut frame_counter: ResMut<FrameCounter>,
-) {
-    frame_counter.0 += 1;
-
-    if mouse_button_input.just_pressed(MouseButton::Middle) {
-        info!("Toggle visibility");
-        ui_infobox_state.visibility = !ui_infobox_state.visibility;
-        frame_counter.0 = 0;
-    }
-
-    if mouse_button_input.just_pressed(MouseButton::Left) {
-        info!("Will show dogs");
-        ui_infobox_state.animal_state = UiInfoboxAnimalsState::Dogs;
-        frame_counter.0 = 0;
-    }
-
-    if mouse_button_input.just_pressed(MouseButton::Right) {
-        info!("Will show cats");
-        if ui_infobox_state.animal_state == UiInfoboxAnimalsState::Cats {
-            if ui_infobox_state.animal_cats_state == UiInfoboxCatsContentState::Kittens {
-                info!("Will show cats facts");
-                ui_infobox_state.animal_cats_state = UiInfoboxCatsContentState::Facts;
-            } else {
-                info!("Will show cat kittens");
-                ui_infobox_state.animal_cats_state = UiInfoboxCatsContentState::Kittens;
-            }
-        } else {
-            ui_infobox_state.animal_state = UiInfoboxAnimalsState::Cats;
-        }
-        frame_counter.0 = 0;
-    }
-}
-
-fn spawn_ui_infobox(
-    mut commands: Commands,
-    ui_root: Query<Entity, With<UiInfoboxRoot>>,
-    ui_infobox_state: Res<UiInfoboxState>,
-    asset_server: Res<AssetServer>,
-    frame_counter: Res<FrameCounter>,
-) {
-    if ui_infobox_state.is_changed() {
-        // Reset ui
-        if let Ok(e) = ui_root.get_single() {
-            commands.entity(e).despawn_recursive();
-        }
-        if ui_infobox_state.visibility {
-            info!("Frame: {:?} infobox root", frame_counter.0);
-            //Spawn root node
-            commands
-                .spawn_bundle(NodeBundle {
-                    color: Color::RED.into(),
-                    style: Style {
-                        size: Size::new(Val::Px(600.0), Val::Px(400.0)),
-                        position_type: PositionType::Absolute,
-                        align_items: AlignItems::FlexStart,
-                        flex_direction: FlexDirection::ColumnReverse,
-                        position: UiRect {
-                            left: Val::Px(20.0),
-                            bottom: Val::Px(10.0),
-                            ..default()
-                        },
-                        ..default()
-                    },
-                    ..default()
-                })
-                .insert(Interaction::None)
-                .insert(UiInfoboxRoot)
-                // Add children some of which can be stateful
-                .with_children(|parent| {
-                    parent.spawn_bundle(TextBundle {
-                        text: Text::from_section(
-                            "Animals".to_owned(),
-                            TextStyle {
-                                font_size: 32.0,
-                                color: Color::BLUE,
-                                font: asset_server.load("font.ttf").clone(),
-                            },
-                        ),
-                        style: Style {
-                            margin: UiRect::all(Val::Px(5.0)),
-                            ..default()
-                        },
-                        ..default()
-                    });
-                    parent
-                        .spawn_bundle(NodeBundle::default())
-                        .insert(UiInfoboxAnimals);
-                });
-        }
-    }
-}
-
-fn spawn_ui_infobox_cats(
-    mut commands: Commands,
-    query: Query<Entity, Added<UiInfoboxAnimals>>,
-    ui_infobox_state: Res<UiInfoboxState>,
-    asset_server: Res<AssetServer>,
-) {
-    for e in query.iter() {
-        if ui_infobox_state.animal_state == UiInfoboxAnimalsState::Cats {
-            commands
-                .entity(e)
-                .insert_bundle(NodeBundle {
-                    color: Color::ORANGE.into(),
-                    style: Style {
-                        flex_direction: FlexDirection::ColumnReverse,
-                        align_items: AlignItems::FlexStart,
-                        size: Size::new(Val::Percent(100.0), Val::Px(300.0)),
-                        ..default()
-                    },
-                    ..default()
-                })
-                .with_children(|parent| {
-                    //Should spawn cat stuff
-                    parent.spawn_bundle(TextBundle {
-                        text: Text::from_section(
-                            "Cat stuff",
-                            TextStyle {
-                                font_size: 20.0,
-                                color: Color::BLUE,
-                                font: asset_server.load("font.ttf").clone(),
-                            },
-                        ),
-                        style: Style {
-                            margin: UiRect::all(Val::Px(5.0)),
-                            ..default()
-                        },
-                        ..default()
-                    });
-                    parent
-                        .spawn_bundle(NodeBundle::default())
-                        .insert(UiInfoboxCat);
-                });
-        }
+    let unit = commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: Color::PURPLE,
+                custom_size: Some(Vec2::splat(40.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(Unit)
+        .id();
+
+    for placement in [
+        Placement::Anchored {
+            anchor: Anchor::TopLeft,
+            offset: Vec2::new(20.0, 10.0),
+        },
+        Placement::Following {
+            entity: unit,
+            anchor: Anchor::BottomCenter,
+            offset: Vec2::new(0.0, -30.0),
+        },
+    ] {
+        commands
+            .spawn()
+            .insert(UiInfoboxRoot)
+            .insert(placement)
+            .insert(PanelGeometry::new(Vec2::ZERO, Vec2::new(600.0, 400.0)))
+            .insert(ResponsivePanel::new(
+                Vec2::new(380.0, 400.0),
+                Vec2::new(600.0, 400.0),
+                Vec2::new(900.0, 600.0),
+            ))
+            .insert(UiInfoboxState::default())
+            .insert(Interaction::None)
+            .insert(Focusable)
+            .insert(AccessibleRole::Panel);
     }
 }
 
-fn spawn_ui_infobox_cats_facts(
-    mut commands: Commands,
-    query: Query<Entity, Added<UiInfoboxCat>>,
-    ui_infobox_state: Res<UiInfoboxState>,
-    asset_server: Res<AssetServer>,
-) {
-    for e in query.iter() {
-        if ui_infobox_state.animal_cats_state == UiInfoboxCatsContentState::Facts {
-            commands
-                .entity(e)
-                .insert_bundle(NodeBundle {
-                    color: Color::GREEN.into(),
-                    style: Style {
-                        flex_direction: FlexDirection::ColumnReverse,
-                        size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
-                        ..default()
-                    },
-                    ..default()
-                })
-                .with_children(|parent| {
-                    //Should spawn cat facts
-                    parent.spawn_bundle(TextBundle {
-                        text: Text::from_section(
-                            "Cats can jump 5 times their own height.",
-                            TextStyle {
-                                font_size: 20.0,
-                                color: Color::BLUE,
-                                font: asset_server.load("font.ttf").clone(),
-                            },
-                        ),
-                        style: Style {
-                            margin: UiRect::all(Val::Px(5.0)),
-                            ..default()
-                        },
-                        ..default()
-                    });
-                });
-        }
+fn move_units(time: Res<Time>, mut units: Query<&mut Transform, With<Unit>>) {
+    let angle = time.seconds_since_startup() as f32 * 0.5;
+    for mut transform in units.iter_mut() {
+        transform.translation = Vec3::new(angle.cos() * 300.0, angle.sin() * 150.0, 0.0);
     }
 }
 
-fn spawn_ui_infobox_cats_kittens(
-    mut commands: Commands,
-    query: Query<Entity, Added<UiInfoboxCat>>,
-    ui_infobox_state: Res<UiInfoboxState>,
-    asset_server: Res<AssetServer>,
-    frame_counter: Res<FrameCounter>,
+/// Logs every few seconds in how many frames the ui schedule ran and in how many it was skipped
+fn log_ui_schedule_metrics(
+    time: Res<Time>,
+    metrics: Res<UiScheduleMetrics>,
+    mut last_logged: Local<f64>,
 ) {
-    for e in query.iter() {
-        if ui_infobox_state.animal_cats_state == UiInfoboxCatsContentState::Kittens {
-            info!("Frame: {:?} infobox kittens", frame_counter.0);
-            commands
-                .entity(e)
-                .insert_bundle(NodeBundle {
-                    color: Color::GREEN.into(),
-                    style: Style {
-                        flex_direction: FlexDirection::ColumnReverse,
-                        size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
-                        ..default()
-                    },
-                    ..default()
-                })
-                .with_children(|parent| {
-                    //Should spawn kittens
-                    parent.spawn_bundle(ImageBundle {
-                        image: asset_server.load("kittens.png").into(),
-                        ..default()
-                    });
-                });
-        }
-    }
-}
-
-///  Dog stuff
-fn spawn_ui_infobox_dogs(
-    mut commands: Commands,
-    query: Query<Entity, Added<UiInfoboxAnimals>>,
-    ui_infobox_state: Res<UiInfoboxState>,
-    asset_server: Res<AssetServer>,
-) {
-    for e in query.iter() {
-        if ui_infobox_state.animal_state == UiInfoboxAnimalsState::Dogs {
-            commands
-                .entity(e)
-                .insert_bundle(NodeBundle {
-                    color: Color::ORANGE.into(),
-                    style: Style {
-                        flex_direction: FlexDirection::ColumnReverse,
-                        align_items: AlignItems::FlexStart,
-                        size: Size::new(Val::Percent(100.0), Val::Px(300.0)),
-                        ..default()
-                    },
-                    ..default()
-                })
-                .with_children(|parent| {
-                    //Should spawn dog stuff
-                    parent.spawn_bundle(TextBundle {
-                        text: Text::from_section(
-                            "Dog stuff",
-                            TextStyle {
-                                font_size: 20.0,
-                                color: Color::YELLOW,
-                                font: asset_server.load("font.ttf").clone(),
-                            },
-                        ),
-                        style: Style {
-                            margin: UiRect::all(Val::Px(5.0)),
-                            ..default()
-                        },
-                        ..default()
-                    });
-
-                    parent.spawn_bundle(ImageBundle {
-                        image: asset_server.load("dog.png").into(),
-                        ..default()
-                    });
-                });
-        }
+    let now = time.seconds_since_startup();
+    if now - *last_logged >= 5.0 {
+        *last_logged = now;
+        info!(
+            "Ui schedule ran in {} frames and was skipped in {}",
+            metrics.run_frames, metrics.skipped_frames
+        );
     }
 }
@@ -0,0 +1,142 @@
+//! Semantic roles for ui nodes and an accessibility tree built from them, which screen reader
+//! integrations can read from the `AccessibilityTree` resource.
+
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+
+/// Builds the accessibility tree of every ui tree whose root carries `T`
+pub struct AccessibilityPlugin<T: Component> {
+    marker: PhantomData<T>,
+}
+
+impl<T: Component> Default for AccessibilityPlugin<T> {
+    fn default() -> Self {
+        Self {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Component> Plugin for AccessibilityPlugin<T> {
+    fn build(&self, app: &mut App) {
+        // Re-renders are only visible once the commands of the update stage are applied
+        app.init_resource::<AccessibilityTree>()
+            .add_system_to_stage(CoreStage::PostUpdate, update_accessibility_tree::<T>);
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessibleRole {
+    Panel,
+    Heading,
+    Text,
+    /// Content of the selected tab
+    Tab,
+    Button,
+    /// Image, described by its `AccessibleLabel`
+    Image,
+}
+
+/// Name of a node, or alt text of an image. Nodes without it are named by their own text or the
+/// text of their children without a role.
+#[derive(Component, Clone, Debug, PartialEq, Eq)]
+pub struct AccessibleLabel(pub String);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccessibilityNode {
+    pub role: AccessibleRole,
+    pub label: String,
+    pub children: Vec<AccessibilityNode>,
+}
+
+/// Snapshot of the accessibility trees, updated every frame. Nodes without a role are left out,
+/// their children take their place.
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct AccessibilityTree {
+    pub roots: Vec<(Entity, AccessibilityNode)>,
+}
+
+type AccessibilityQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Option<&'static AccessibleRole>,
+        Option<&'static AccessibleLabel>,
+        Option<&'static Text>,
+        Option<&'static Visibility>,
+        Option<&'static Children>,
+    ),
+>;
+
+fn text_value(text: &Text) -> String {
+    text.sections
+        .iter()
+        .map(|section| section.value.as_str())
+        .collect()
+}
+
+fn label(entity: Entity, nodes: &AccessibilityQuery) -> String {
+    let (_, label, text, _, children) = match nodes.get(entity) {
+        Ok(node) => node,
+        Err(_) => return String::new(),
+    };
+    if let Some(label) = label {
+        return label.0.clone();
+    }
+    if let Some(text) = text {
+        return text_value(text);
+    }
+    children
+        .into_iter()
+        .flat_map(|children| children.iter())
+        .filter_map(|child| match nodes.get(*child) {
+            Ok((None, _, Some(text), _, _)) => Some(text_value(text)),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Appends the accessibility nodes of `entity` and its visible descendants to `out`
+fn collect_nodes(entity: Entity, nodes: &AccessibilityQuery, out: &mut Vec<AccessibilityNode>) {
+    let (role, _, _, visibility, children) = match nodes.get(entity) {
+        Ok(node) => node,
+        Err(_) => return,
+    };
+    if visibility.is_some_and(|visibility| !visibility.is_visible) {
+        return;
+    }
+    let mut child_nodes = Vec::new();
+    for child in children.into_iter().flat_map(|children| children.iter()) {
+        collect_nodes(*child, nodes, &mut child_nodes);
+    }
+    match role {
+        Some(role) => out.push(AccessibilityNode {
+            role: *role,
+            label: label(entity, nodes),
+            children: child_nodes,
+        }),
+        None => out.append(&mut child_nodes),
+    }
+}
+
+fn update_accessibility_tree<T: Component>(
+    mut tree: ResMut<AccessibilityTree>,
+    roots: Query<Entity, With<T>>,
+    nodes: AccessibilityQuery,
+) {
+    let mut snapshot = AccessibilityTree::default();
+    for root in roots.iter() {
+        let mut root_nodes = Vec::new();
+        collect_nodes(root, &nodes, &mut root_nodes);
+        snapshot
+            .roots
+            .extend(root_nodes.into_iter().map(|node| (root, node)));
+    }
+    snapshot.roots.sort_by_key(|(root, _)| *root);
+    // Keep change detection meaningful for readers of the tree
+    if *tree != snapshot {
+        *tree = snapshot;
+    }
+}
@@ -0,0 +1,422 @@
+//! Stateful ui branches, independent of the strategy running them. A branch fills a slot with
+//! content depending on its governing state, and the runner decides when:
+//!
+//! * `spawn_added_branches`: the state is a component on the slot or one of its ancestors.
+//!   Content is built when the slot is added, and rebuilt when the slot carries the state itself
+//!   and it changes. Every level of the tree takes a frame.
+//! * `spawn_registered_branches`: the state of every ui tree is kept in the `UiSlotRegistry`
+//!   resource, along with the slots and the state they were built for. A state change only
+//!   rebuilds the branches whose governing state changed, see `UiBranch::changed`.
+//! * `UiSchedule`: runs either of them with each level in its own stage, so commands are applied
+//!   in between and the whole tree is built in one frame. With `until_settled` the stages are run
+//!   again while they build anything, for trees deeper than the stages. `add_ui_schedule` skips
+//!   the schedule in frames where none of its states changed and none of its slots were added.
+//!
+//! `#[derive(UiBranch)]` implements the trait for a state enum, building the content of the
+//! current variant:
+//!
+//! ```ignore
+//! #[derive(UiBranch)]
+//! #[ui_branch(slot = UiInfoboxAnimals, state = UiInfoboxState, field = animal_state)]
+//! #[ui_branch(context = InfoboxContext<'static, 'static>)]
+//! enum UiInfoboxAnimalsState {
+//!     #[ui_branch(build = spawn_ui_infobox_dogs)]
+//!     Dogs,
+//!     #[ui_branch(build = spawn_ui_infobox_cats)]
+//!     Cats,
+//! }
+//! ```
+//!
+//! The derive doesn't pick a strategy, the runner the branch is added to does:
+//! `spawn_added_branches::<B>` or `UiSchedule::add_branch::<B>` for the state components, and
+//! `spawn_registered_branches::<B>` or `UiSchedule::add_registered_branch::<B>` for the resource
+//! registry. Fields of the state besides `field` which the content depends on are listed with
+//! `rebuild_on = ...`.
+
+use bevy::{
+    ecs::{
+        entity::Entities,
+        schedule::ShouldRun,
+        system::{EntityCommands, StaticSystemParam, SystemParam, SystemParamItem},
+    },
+    prelude::*,
+    utils::HashMap,
+};
+
+use crate::builder::find_ancestor;
+
+pub use bevy_ui_pattern_example_derive::UiBranch;
+
+pub trait UiBranch: Send + Sync + 'static {
+    /// Marker of the slots the branch is built into
+    type Slot: Component;
+    /// State the content depends on
+    type State: Component;
+    /// System params the build needs, with `'static` lifetimes
+    type Context: SystemParam;
+
+    /// Builds the content for `state` into the slot
+    fn build(
+        slot: &mut EntityCommands,
+        state: &Self::State,
+        context: &mut SystemParamItem<Self::Context>,
+    );
+
+    /// Whether content built for `old` is outdated for `new`, which defaults to any change.
+    /// Only runners which remember the built state can tell, the others rebuild on any change.
+    fn changed(_old: &Self::State, _new: &Self::State) -> bool {
+        true
+    }
+}
+
+/// Number of slots the runners built, counted while the resource exists
+pub struct UiBranchBuilds(pub usize);
+
+/// Slots of `B` which were added or whose own state changed
+type ChangedSlotQuery<'w, 's, B> = Query<
+    'w,
+    's,
+    (Entity, ChangeTrackers<<B as UiBranch>::Slot>),
+    (
+        With<<B as UiBranch>::Slot>,
+        Or<(
+            Added<<B as UiBranch>::Slot>,
+            Changed<<B as UiBranch>::State>,
+        )>,
+    ),
+>;
+
+/// Builds `B` into new slots, and rebuilds slots carrying their own state when it changes
+pub fn spawn_added_branches<B: UiBranch>(
+    mut commands: Commands,
+    slots: ChangedSlotQuery<B>,
+    parents: Query<&Parent>,
+    states: Query<&B::State>,
+    mut context: StaticSystemParam<B::Context>,
+    builds: Option<ResMut<UiBranchBuilds>>,
+) {
+    let mut built = 0;
+    for (e, tracker) in slots.iter() {
+        let state = match find_ancestor(e, &parents, &states) {
+            Some(state) => state,
+            None => continue,
+        };
+        if !tracker.is_added() {
+            // Reset ui, the slot itself is kept since it owns the state
+            commands.entity(e).despawn_descendants();
+        }
+        B::build(&mut commands.entity(e), state, &mut *context);
+        built += 1;
+    }
+    if let Some(mut builds) = builds {
+        builds.0 += built;
+    }
+}
+
+/// Branches run level by level, see `run_ui_schedule`
+#[derive(Default)]
+pub struct UiSchedule {
+    schedule: Schedule,
+    stages: Vec<&'static str>,
+    /// Most passes through the stages per run, `None` runs them once
+    max_passes: Option<usize>,
+    /// Adds the change detection of each branch, see `add_ui_schedule`
+    change_detection: Vec<fn(&mut App)>,
+    /// Whether a state changed or a slot was added since the last run
+    changed: bool,
+}
+
+impl UiSchedule {
+    /// Runs `B` with `spawn_added_branches` in `stage`, which is added after the other stages if
+    /// it is new
+    pub fn add_branch<B: UiBranch>(&mut self, stage: &'static str) -> &mut Self {
+        self.add_stage(stage)
+            .add_system_to_stage(stage, spawn_added_branches::<B>);
+        self.change_detection.push(add_change_detection::<B>);
+        self
+    }
+
+    /// Runs `B` with `spawn_registered_branches` in `stage`, like `add_branch`. Put the branches
+    /// in stages from the outside in, a rebuild forgets the slots it despawns.
+    pub fn add_registered_branch<B: UiBranch>(&mut self, stage: &'static str) -> &mut Self
+    where
+        B::State: Clone,
+    {
+        self.add_stage(stage)
+            .add_system_to_stage(stage, spawn_registered_branches::<B>);
+        self.change_detection
+            .push(add_registry_change_detection::<B>);
+        self
+    }
+
+    fn add_stage(&mut self, stage: &'static str) -> &mut Schedule {
+        if !self.stages.contains(&stage) {
+            self.schedule.add_stage(stage, SystemStage::parallel());
+            self.stages.push(stage);
+        }
+        &mut self.schedule
+    }
+
+    /// Runs the stages again until a pass builds nothing, so slots added by the last stage are
+    /// filled in the same frame. Up to `max_passes` passes may build, followed by the pass which
+    /// confirms nothing is left. Gives up with a warning if that one builds anything too.
+    pub fn until_settled(&mut self, max_passes: usize) -> &mut Self {
+        self.max_passes = Some(max_passes);
+        self
+    }
+}
+
+/// Runs the `UiSchedule` resource, add it as an exclusive system
+pub fn run_ui_schedule(world: &mut World) {
+    world.resource_scope(|world, mut ui_schedule: Mut<UiSchedule>| {
+        let max_passes = match ui_schedule.max_passes {
+            Some(max_passes) => max_passes,
+            None => {
+                ui_schedule.schedule.run_once(world);
+                return;
+            }
+        };
+        // The confirming pass comes on top of the building ones
+        for _ in 0..=max_passes {
+            world.insert_resource(UiBranchBuilds(0));
+            ui_schedule.schedule.run_once(world);
+            if world.resource::<UiBranchBuilds>().0 == 0 {
+                return;
+            }
+        }
+        warn!("Ui schedule didn't settle within {} passes", max_passes);
+    });
+}
+
+/// Frames in which `add_ui_schedule` ran or skipped the schedule
+#[derive(Default, Debug)]
+pub struct UiScheduleMetrics {
+    pub run_frames: u64,
+    pub skipped_frames: u64,
+}
+
+pub trait AddUiSchedule {
+    /// Runs `ui_schedule` as an exclusive system, but only in frames after a state of its
+    /// branches changed or one of their slots was added. Slots added by the schedule itself count
+    /// too, so a rebuild is followed by one run which finds nothing to do.
+    fn add_ui_schedule(&mut self, ui_schedule: UiSchedule) -> &mut Self;
+}
+
+impl AddUiSchedule for App {
+    fn add_ui_schedule(&mut self, mut ui_schedule: UiSchedule) -> &mut Self {
+        for add_change_detection in std::mem::take(&mut ui_schedule.change_detection) {
+            add_change_detection(self);
+        }
+        self.insert_resource(ui_schedule)
+            .init_resource::<UiScheduleMetrics>()
+            .add_system(
+                run_ui_schedule
+                    .exclusive_system()
+                    .with_run_criteria(ui_schedule_changed),
+            )
+    }
+}
+
+fn add_change_detection<B: UiBranch>(app: &mut App) {
+    // Before the update stage, whose start the schedule runs at
+    app.add_system_to_stage(CoreStage::PreUpdate, detect_ui_changes::<B>);
+}
+
+fn detect_ui_changes<B: UiBranch>(
+    slots: Query<(), Added<B::Slot>>,
+    states: Query<(), Changed<B::State>>,
+    mut ui_schedule: ResMut<UiSchedule>,
+) {
+    if !slots.is_empty() || !states.is_empty() {
+        ui_schedule.changed = true;
+    }
+}
+
+fn add_registry_change_detection<B: UiBranch>(app: &mut App)
+where
+    B::State: Clone,
+{
+    app.add_system_to_stage(CoreStage::PreUpdate, detect_registry_changes::<B>);
+}
+
+/// The registry is only changed by state changes and by rebuilds, see
+/// `spawn_registered_branches`. Despawned slots and trees have to be forgotten too.
+fn detect_registry_changes<B: UiBranch>(
+    slots: Query<(), Added<B::Slot>>,
+    registry: Res<UiSlotRegistry<B::State>>,
+    entities: &Entities,
+    mut ui_schedule: ResMut<UiSchedule>,
+) {
+    if !slots.is_empty() || registry.is_changed() || registry.is_stale(entities) {
+        ui_schedule.changed = true;
+    }
+}
+
+fn ui_schedule_changed(
+    mut ui_schedule: ResMut<UiSchedule>,
+    mut metrics: ResMut<UiScheduleMetrics>,
+) -> ShouldRun {
+    if std::mem::take(&mut ui_schedule.changed) {
+        metrics.run_frames += 1;
+        ShouldRun::Yes
+    } else {
+        metrics.skipped_frames += 1;
+        ShouldRun::No
+    }
+}
+
+/// A slot in a `UiSlotRegistry`
+struct RegisteredSlot<S> {
+    /// Root of the ui tree of the slot, whose state governs it
+    root: Entity,
+    /// State the content of the slot was built for
+    built: S,
+}
+
+/// States `S` of ui trees, keyed by the root of the tree, and the slots of the branches they
+/// govern
+pub struct UiSlotRegistry<S> {
+    states: HashMap<Entity, S>,
+    slots: HashMap<Entity, RegisteredSlot<S>>,
+}
+
+impl<S> Default for UiSlotRegistry<S> {
+    fn default() -> Self {
+        Self {
+            states: HashMap::default(),
+            slots: HashMap::default(),
+        }
+    }
+}
+
+impl<S> UiSlotRegistry<S> {
+    /// Governs the ui tree below `root`, including `root` itself, by `state`
+    pub fn insert_tree(&mut self, root: Entity, state: S) {
+        self.states.insert(root, state);
+    }
+
+    pub fn state(&self, root: Entity) -> Option<&S> {
+        self.states.get(&root)
+    }
+
+    pub fn state_mut(&mut self, root: Entity) -> Option<&mut S> {
+        self.states.get_mut(&root)
+    }
+
+    /// The state of every ui tree along with its root
+    pub fn states_mut(&mut self) -> impl Iterator<Item = (Entity, &mut S)> {
+        self.states.iter_mut().map(|(root, state)| (*root, state))
+    }
+
+    pub fn contains(&self, slot: Entity) -> bool {
+        self.slots.contains_key(&slot)
+    }
+
+    /// Root of the ui tree `entity` is in, the nearest of it and its ancestors with a state
+    fn tree_root(&self, mut entity: Entity, parents: &Query<&Parent>) -> Option<Entity> {
+        loop {
+            if self.states.contains_key(&entity) {
+                return Some(entity);
+            }
+            entity = parents.get(entity).ok()?.get();
+        }
+    }
+
+    /// Forgets the registered slots below `entity`, which is about to be rebuilt
+    fn forget_descendants(&mut self, entity: Entity, children: &Query<&Children>) {
+        for child in children.get(entity).into_iter().flatten() {
+            self.slots.remove(child);
+            self.forget_descendants(*child, children);
+        }
+    }
+
+    /// Whether a ui tree or slot was despawned
+    fn is_stale(&self, entities: &Entities) -> bool {
+        self.states.keys().any(|root| !entities.contains(*root))
+            || self.slots.keys().any(|e| !entities.contains(*e))
+    }
+
+    /// Forgets despawned ui trees and their slots. Rebuilds forget the slots they despawn, so a
+    /// despawned slot in a live tree was despawned behind the registry's back, which is a bug.
+    fn invalidate(&mut self, entities: &Entities) {
+        self.states.retain(|root, _| entities.contains(*root));
+        self.slots.retain(|e, slot| {
+            if entities.contains(*e) {
+                return true;
+            }
+            let tree_alive = entities.contains(slot.root);
+            if tree_alive {
+                error!("Stale ui slot {:?}", e);
+            }
+            debug_assert!(!tree_alive, "stale ui slot {:?}", e);
+            false
+        });
+    }
+}
+
+/// Builds `B` into new slots of the registered ui trees and registers them. When the state of
+/// a tree changes, its slots of `B` are rebuilt if `B::changed` says their content is outdated.
+/// Run the branches from the outside in, a rebuild forgets the slots it despawns. The registry
+/// is only borrowed mutably when there's something to do, so idle frames don't change it.
+pub fn spawn_registered_branches<B: UiBranch>(
+    mut commands: Commands,
+    mut registry: ResMut<UiSlotRegistry<B::State>>,
+    slots: Query<(Entity, ChangeTrackers<B::Slot>)>,
+    (parents, children): (Query<&Parent>, Query<&Children>),
+    entities: &Entities,
+    mut context: StaticSystemParam<B::Context>,
+    builds: Option<ResMut<UiBranchBuilds>>,
+) where
+    B::State: Clone,
+{
+    if registry.is_stale(entities) {
+        registry.invalidate(entities);
+    }
+
+    let added: Vec<(Entity, Entity)> = slots
+        .iter()
+        .filter(|(_, tracker)| tracker.is_added())
+        .filter_map(|(e, _)| Some((e, registry.tree_root(e, &parents)?)))
+        .collect();
+    let rebuilt: Vec<Entity> = registry
+        .slots
+        .iter()
+        .filter(|(e, _)| slots.get(**e).is_ok_and(|(_, tracker)| !tracker.is_added()))
+        .filter(|(_, slot)| {
+            registry
+                .states
+                .get(&slot.root)
+                .is_some_and(|state| B::changed(&slot.built, state))
+        })
+        .map(|(e, _)| *e)
+        .collect();
+    if added.is_empty() && rebuilt.is_empty() {
+        return;
+    }
+
+    let registry = &mut *registry;
+    let mut built = added.len();
+    for (e, root) in added {
+        let state = &registry.states[&root];
+        B::build(&mut commands.entity(e), state, &mut *context);
+        let slot = RegisteredSlot {
+            root,
+            built: state.clone(),
+        };
+        registry.slots.insert(e, slot);
+    }
+    for e in rebuilt {
+        registry.forget_descendants(e, &children);
+        let slot = match registry.slots.get_mut(&e) {
+            Some(slot) => slot,
+            None => continue,
+        };
+        slot.built = registry.states[&slot.root].clone();
+        commands.entity(e).despawn_descendants();
+        B::build(&mut commands.entity(e), &slot.built, &mut *context);
+        built += 1;
+    }
+    if let Some(mut builds) = builds {
+        builds.0 += built;
+    }
+}
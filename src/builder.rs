@@ -0,0 +1,101 @@
+//! Declarative ui trees. `ui!` spawns a tree of bundles and components, e.g. the content of a
+//! `branch::UiBranch`.
+//!
+//! ```ignore
+//! ui!(commands.entity(e) => [node(Color::RED, Style::default()), AccessibleRole::Panel] {
+//!     [text(font.clone(), 20.0, Color::BLUE), LocalizedText("infobox-title")];
+//!     [slot(), UiInfoboxAnimals];
+//!     with(|parent| spawn_resize_handles(parent, e));
+//! });
+//! ```
+//!
+//! Every node is a bundle followed by its components in brackets, with its children in braces or
+//! a `;` if it has none. `with(f)` calls `f` with the `ChildBuilder` of the enclosing node.
+
+use bevy::prelude::*;
+
+/// Node with a background `color`
+pub fn node(color: Color, style: Style) -> NodeBundle {
+    NodeBundle {
+        color: color.into(),
+        style,
+        ..default()
+    }
+}
+
+/// Empty text node, for texts which are filled in later, e.g. by `LocalizedText`
+pub fn text(font: Handle<Font>, font_size: f32, color: Color) -> TextBundle {
+    TextBundle {
+        text: Text::from_section(
+            String::new(),
+            TextStyle {
+                font_size,
+                color,
+                font,
+            },
+        ),
+        style: Style {
+            margin: UiRect::all(Val::Px(5.0)),
+            ..default()
+        },
+        ..default()
+    }
+}
+
+/// Placeholder for a stateful branch, which inserts its own bundle once spawned
+pub fn slot() -> NodeBundle {
+    NodeBundle::default()
+}
+
+/// Walks up the hierarchy from `entity` to the nearest entity with a `T`
+pub fn find_ancestor<'a, T: Component>(
+    mut entity: Entity,
+    parents: &Query<&Parent>,
+    query: &'a Query<&T>,
+) -> Option<&'a T> {
+    loop {
+        if let Ok(component) = query.get(entity) {
+            return Some(component);
+        }
+        entity = parents.get(entity).ok()?.get();
+    }
+}
+
+#[macro_export]
+macro_rules! ui {
+    (@children $parent:ident) => {};
+    (@children $parent:ident with($f:expr); $($rest:tt)*) => {
+        ($f)(&mut *$parent);
+        $crate::ui!(@children $parent $($rest)*);
+    };
+    (@children $parent:ident [$bundle:expr $(, $component:expr)* $(,)?]; $($rest:tt)*) => {
+        $parent.spawn_bundle($bundle)$(.insert($component))*;
+        $crate::ui!(@children $parent $($rest)*);
+    };
+    (
+        @children $parent:ident
+        [$bundle:expr $(, $component:expr)* $(,)?] { $($children:tt)* }
+        $($rest:tt)*
+    ) => {
+        $parent
+            .spawn_bundle($bundle)
+            $(.insert($component))*
+            .with_children(|parent| {
+                $crate::ui!(@children parent $($children)*);
+            });
+        $crate::ui!(@children $parent $($rest)*);
+    };
+    ($entity:expr => [$bundle:expr $(, $component:expr)* $(,)?] { $($children:tt)* }) => {
+        $entity
+            .insert_bundle($bundle)
+            $(.insert($component))*
+            .with_children(|parent| {
+                $crate::ui!(@children parent $($children)*);
+            })
+    };
+    ($entity:expr => { $($children:tt)* }) => {
+        $entity.with_children(|parent| {
+            $crate::ui!(@children parent $($children)*);
+        })
+    };
+}
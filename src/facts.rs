@@ -0,0 +1,189 @@
+//! Data driven catalogue of facts, loaded from a `.facts.ron` asset of the current locale
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::{
+    accessibility::AccessibleRole,
+    locale::{Locale, LocalizedLabel},
+    rich_text::{escape, set_rich_text, RichTextFonts},
+};
+
+pub struct FactsPlugin;
+
+impl Plugin for FactsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Locale>()
+            .init_resource::<RichTextFonts>()
+            .add_asset::<FactCatalogue>()
+            .init_asset_loader::<FactCatalogueLoader>()
+            .add_startup_system(load_cat_facts)
+            .add_system(reload_cat_facts)
+            .add_system(update_fact_texts.after(reload_cat_facts));
+    }
+}
+
+#[derive(Deserialize)]
+pub struct Fact {
+    /// Rich text markup, see `rich_text`
+    pub text: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Deserialize, TypeUuid)]
+#[uuid = "385840ae-e7dd-4f70-a408-7fbd5153ab87"]
+pub struct FactCatalogue {
+    pub facts: Vec<Fact>,
+}
+
+impl FactCatalogue {
+    /// Index of the fact shown after `navigation`, wrapping around at both ends
+    pub fn navigate(&self, index: usize, navigation: FactNavigation) -> usize {
+        let len = self.facts.len();
+        if len < 2 {
+            return 0;
+        }
+        match navigation {
+            FactNavigation::Previous => (index + len - 1) % len,
+            FactNavigation::Next => (index + 1) % len,
+            // Never pick the fact which is already shown
+            FactNavigation::Random => (index + rand::thread_rng().gen_range(1..len)) % len,
+        }
+    }
+}
+
+#[derive(Default)]
+struct FactCatalogueLoader;
+
+impl AssetLoader for FactCatalogueLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let catalogue: FactCatalogue = ron::de::from_bytes(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(catalogue));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["facts.ron"]
+    }
+}
+
+/// Handle to the cat facts of the current locale
+pub struct CatFacts(pub Handle<FactCatalogue>);
+
+fn load_cat_facts(mut commands: Commands, asset_server: Res<AssetServer>, locale: Res<Locale>) {
+    commands.insert_resource(CatFacts(
+        asset_server.load(&locale.asset_path("cat_facts.facts.ron")),
+    ));
+}
+
+fn reload_cat_facts(
+    mut cat_facts: ResMut<CatFacts>,
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+) {
+    if locale.is_changed() && !locale.is_added() {
+        cat_facts.0 = asset_server.load(&locale.asset_path("cat_facts.facts.ron"));
+    }
+}
+
+/// Button moving through the catalogue
+#[derive(Component, Clone, Copy)]
+pub enum FactNavigation {
+    Previous,
+    Next,
+    Random,
+}
+
+/// Text node showing the fact at `index`. Filled in once the catalogue is loaded and refreshed
+/// when it is hot reloaded.
+#[derive(Component)]
+pub struct FactText {
+    pub index: usize,
+}
+
+fn update_fact_texts(
+    mut events: EventReader<AssetEvent<FactCatalogue>>,
+    cat_facts: Res<CatFacts>,
+    catalogues: Res<Assets<FactCatalogue>>,
+    fonts: Res<RichTextFonts>,
+    mut texts: Query<(&FactText, &mut Text, ChangeTrackers<FactText>)>,
+) {
+    // A previously loaded catalogue is available right away and sends no event
+    let reloaded = events.iter().count() > 0 || cat_facts.is_changed();
+    let catalogue = match catalogues.get(&cat_facts.0) {
+        Some(catalogue) => catalogue,
+        None => return,
+    };
+    for (fact_text, mut text, tracker) in texts.iter_mut() {
+        if !reloaded && !tracker.is_added() {
+            continue;
+        }
+        // Fact texts are markup, tags are shown as is
+        let markup = match catalogue.facts.get(fact_text.index) {
+            Some(fact) if fact.tags.is_empty() => fact.text.clone(),
+            Some(fact) => format!("{}\n[gray]#{}[/]", fact.text, escape(&fact.tags.join(" #"))),
+            None => String::new(),
+        };
+        set_rich_text(&mut text, &markup, &fonts);
+    }
+}
+
+/// Spawns the previous/random/next buttons of a facts branch
+pub fn spawn_fact_navigation(parent: &mut ChildBuilder, font: Handle<Font>) {
+    parent
+        .spawn_bundle(NodeBundle {
+            color: Color::NONE.into(),
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            for (navigation, label, key) in [
+                (FactNavigation::Previous, "<", "fact-previous"),
+                (FactNavigation::Random, "?", "fact-random"),
+                (FactNavigation::Next, ">", "fact-next"),
+            ] {
+                parent
+                    .spawn_bundle(ButtonBundle {
+                        color: Color::DARK_GREEN.into(),
+                        style: Style {
+                            margin: UiRect::all(Val::Px(5.0)),
+                            padding: UiRect::all(Val::Px(5.0)),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(navigation)
+                    .insert(AccessibleRole::Button)
+                    .insert(LocalizedLabel(key.to_owned()))
+                    .with_children(|parent| {
+                        parent.spawn_bundle(TextBundle {
+                            text: Text::from_section(
+                                label,
+                                TextStyle {
+                                    font_size: 20.0,
+                                    color: Color::BLUE,
+                                    font: font.clone(),
+                                },
+                            ),
+                            ..default()
+                        });
+                    });
+            }
+        });
+}
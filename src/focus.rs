@@ -0,0 +1,316 @@
+//! Keyboard focus for ui nodes. Tab/Shift+Tab walk the focusable nodes in reading order, the
+//! arrow keys move to the nearest one in that direction, and Enter/Space activate the focused
+//! node. The focused node is outlined by a focus ring.
+
+use std::cmp::Ordering;
+
+use bevy::{
+    prelude::*,
+    ui::{CalculatedClip, FocusPolicy, UiSystem},
+};
+
+use crate::{
+    hover::{node_rect, node_visible, ui_tree_root},
+    layout::set_if_changed,
+};
+
+const FOCUS_RING_WIDTH: f32 = 3.0;
+const FOCUS_RING_COLOR: Color = Color::ORANGE;
+
+pub struct FocusPlugin;
+
+impl Plugin for FocusPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Focus>()
+            .add_event::<FocusActivated>()
+            .add_startup_system(spawn_focus_ring)
+            // After bevy's own focus system, so simulated clicks aren't reset in the same frame
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                keyboard_focus_system.after(UiSystem::Focus),
+            )
+            .add_system(update_focus_ring);
+    }
+}
+
+/// Marks a ui node which can get keyboard focus. Buttons can always get focus.
+#[derive(Component)]
+pub struct Focusable;
+
+#[derive(Default)]
+pub struct Focus {
+    pub entity: Option<Entity>,
+    /// Ui tree and tab index of the focused node, to find it again after a re-render
+    restore: Option<(Entity, usize)>,
+}
+
+/// Sent when Enter or Space is pressed on the focused node, with the mouse button they stand
+/// in for. Focused buttons are clicked as well.
+pub struct FocusActivated {
+    pub entity: Entity,
+    pub button: MouseButton,
+}
+
+#[derive(Component)]
+struct FocusRing;
+
+struct FocusTarget {
+    entity: Entity,
+    root: Entity,
+    center: Vec2,
+}
+
+fn spawn_focus_ring(mut commands: Commands) {
+    let edges = [
+        (
+            UiRect {
+                left: Val::Px(0.0),
+                top: Val::Px(0.0),
+                ..default()
+            },
+            Size::new(Val::Px(FOCUS_RING_WIDTH), Val::Percent(100.0)),
+        ),
+        (
+            UiRect {
+                right: Val::Px(0.0),
+                top: Val::Px(0.0),
+                ..default()
+            },
+            Size::new(Val::Px(FOCUS_RING_WIDTH), Val::Percent(100.0)),
+        ),
+        (
+            UiRect {
+                left: Val::Px(0.0),
+                top: Val::Px(0.0),
+                ..default()
+            },
+            Size::new(Val::Percent(100.0), Val::Px(FOCUS_RING_WIDTH)),
+        ),
+        (
+            UiRect {
+                left: Val::Px(0.0),
+                bottom: Val::Px(0.0),
+                ..default()
+            },
+            Size::new(Val::Percent(100.0), Val::Px(FOCUS_RING_WIDTH)),
+        ),
+    ];
+    commands
+        .spawn_bundle(NodeBundle {
+            color: Color::NONE.into(),
+            style: Style {
+                display: Display::None,
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            focus_policy: FocusPolicy::Pass,
+            ..default()
+        })
+        .insert(FocusRing)
+        .with_children(|parent| {
+            for (position, size) in edges {
+                parent.spawn_bundle(NodeBundle {
+                    color: FOCUS_RING_COLOR.into(),
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        position,
+                        size,
+                        ..default()
+                    },
+                    focus_policy: FocusPolicy::Pass,
+                    ..default()
+                });
+            }
+        });
+}
+
+/// Index of the nearest target in `direction`, preferring targets in line with the current one
+fn nearest_in_direction(targets: &[FocusTarget], from: Vec2, direction: Vec2) -> Option<usize> {
+    targets
+        .iter()
+        .enumerate()
+        .filter_map(|(index, target)| {
+            let offset = target.center - from;
+            let along = offset.dot(direction);
+            let across = offset.perp_dot(direction).abs();
+            (along > 0.0).then_some((index, along + 2.0 * across))
+        })
+        .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+        .map(|(index, _)| index)
+}
+
+type FocusableQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Node,
+        &'static GlobalTransform,
+        Option<&'static CalculatedClip>,
+    ),
+    Or<(With<Focusable>, With<Button>)>,
+>;
+
+#[allow(clippy::too_many_arguments)]
+fn keyboard_focus_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    windows: Res<Windows>,
+    mut focus: ResMut<Focus>,
+    mut activations: EventWriter<FocusActivated>,
+    focusables: FocusableQuery,
+    nodes: Query<(&Node, &GlobalTransform)>,
+    parents: Query<&Parent>,
+    mut interactions: Query<&mut Interaction, With<Button>>,
+    mut clicked: Local<Vec<Entity>>,
+) {
+    // Release the buttons clicked with the keyboard last frame
+    for e in clicked.drain(..) {
+        if let Ok(mut interaction) = interactions.get_mut(e) {
+            if *interaction == Interaction::Clicked {
+                *interaction = Interaction::None;
+            }
+        }
+    }
+
+    // Reading order: ui tree by ui tree, top to bottom and left to right within each
+    let top_left = |node: &Node, transform: &GlobalTransform, clip: Option<&CalculatedClip>| {
+        let (min, max) = node_rect(node, transform, clip);
+        [-max.y, min.x]
+    };
+    let mut targets: Vec<([f32; 4], FocusTarget)> = focusables
+        .iter()
+        .filter(|(_, node, transform, clip)| node_visible(node, transform, *clip, &windows))
+        .map(|(e, node, transform, clip)| {
+            let root = ui_tree_root(e, &parents);
+            let [root_top, root_left] = nodes
+                .get(root)
+                .map(|(node, transform)| top_left(node, transform, None))
+                .unwrap_or_default();
+            let [top, left] = top_left(node, transform, clip);
+            let (min, max) = node_rect(node, transform, clip);
+            let target = FocusTarget {
+                entity: e,
+                root,
+                center: (min + max) / 2.0,
+            };
+            ([root_top, root_left, top, left], target)
+        })
+        .collect();
+    targets.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    let targets: Vec<FocusTarget> = targets.into_iter().map(|(_, target)| target).collect();
+    let index_in_tree = |index: usize| {
+        targets[..index]
+            .iter()
+            .filter(|target| target.root == targets[index].root)
+            .count()
+    };
+
+    let mut current = focus
+        .entity
+        .and_then(|e| targets.iter().position(|target| target.entity == e));
+    match current {
+        Some(index) => focus.restore = Some((targets[index].root, index_in_tree(index))),
+        None => {
+            focus.entity = None;
+            // Re-rendered nodes are only focusable once laid out, so this may take a few frames
+            if let Some((root, index)) = focus.restore {
+                current = targets
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, target)| target.root == root)
+                    .nth(index)
+                    .map(|(index, _)| index);
+                focus.entity = current.map(|index| targets[index].entity);
+            }
+        }
+    }
+
+    let shift = keyboard_input.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    let direction = [
+        (KeyCode::Up, Vec2::Y),
+        (KeyCode::Down, -Vec2::Y),
+        (KeyCode::Left, -Vec2::X),
+        (KeyCode::Right, Vec2::X),
+    ]
+    .into_iter()
+    .find(|(key, _)| keyboard_input.just_pressed(*key))
+    .map(|(_, direction)| direction);
+    let target = if targets.is_empty() {
+        None
+    } else if keyboard_input.just_pressed(KeyCode::Tab) {
+        Some(match (current, shift) {
+            (Some(index), false) => (index + 1) % targets.len(),
+            (Some(index), true) => (index + targets.len() - 1) % targets.len(),
+            (None, false) => 0,
+            (None, true) => targets.len() - 1,
+        })
+    } else if let Some(direction) = direction {
+        match current {
+            Some(index) => nearest_in_direction(&targets, targets[index].center, direction),
+            None => Some(0),
+        }
+    } else {
+        None
+    };
+    if let Some(index) = target {
+        focus.entity = Some(targets[index].entity);
+        focus.restore = Some((targets[index].root, index_in_tree(index)));
+    }
+
+    let focused = match focus.entity {
+        Some(focused) => focused,
+        None => return,
+    };
+    for (key, button) in [
+        (KeyCode::Return, MouseButton::Left),
+        (KeyCode::Space, MouseButton::Right),
+    ] {
+        if !keyboard_input.just_pressed(key) {
+            continue;
+        }
+        if let Ok(mut interaction) = interactions.get_mut(focused) {
+            *interaction = Interaction::Clicked;
+            clicked.push(focused);
+        }
+        activations.send(FocusActivated {
+            entity: focused,
+            button,
+        });
+    }
+}
+
+fn update_focus_ring(
+    focus: Res<Focus>,
+    nodes: Query<(&Node, &GlobalTransform, Option<&CalculatedClip>)>,
+    mut rings: Query<&mut Style, With<FocusRing>>,
+) {
+    let rect = focus
+        .entity
+        .and_then(|e| nodes.get(e).ok())
+        .map(|(node, transform, clip)| node_rect(node, transform, clip));
+    for mut style in rings.iter_mut() {
+        let (display, position, size) = match rect {
+            // Ui layout is y-up here, so `top` is the distance from the bottom of the window
+            Some((min, max)) => (
+                Display::Flex,
+                UiRect {
+                    left: Val::Px(min.x - FOCUS_RING_WIDTH),
+                    top: Val::Px(min.y - FOCUS_RING_WIDTH),
+                    ..default()
+                },
+                Size::new(
+                    Val::Px(max.x - min.x + 2.0 * FOCUS_RING_WIDTH),
+                    Val::Px(max.y - min.y + 2.0 * FOCUS_RING_WIDTH),
+                ),
+            ),
+            None => (Display::None, style.position, style.size),
+        };
+        let new_style = Style {
+            display,
+            position,
+            size,
+            ..style.clone()
+        };
+        set_if_changed(&mut style, new_style);
+    }
+}
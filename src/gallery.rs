@@ -0,0 +1,216 @@
+//! Image gallery showing thumbnails of a folder or list of images, with a larger view opened by
+//! clicking a thumbnail. Images are only requested once their thumbnail is on screen. The alt
+//! text of an image is the message `alt_text_key(path)` of the current locale.
+
+use std::path::Path;
+
+use bevy::{prelude::*, ui::CalculatedClip};
+
+use crate::{accessibility::AccessibleRole, hover::node_visible, locale::LocalizedLabel};
+
+const THUMBNAIL_SIZE: f32 = 96.0;
+
+pub struct GalleryPlugin;
+
+impl Plugin for GalleryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(gallery_click_system)
+            .add_system(spawn_gallery.after(gallery_click_system))
+            .add_system(load_visible_thumbnails);
+    }
+}
+
+/// Stateful gallery node, its children are rebuilt whenever it changes
+#[derive(Component)]
+pub struct Gallery {
+    images: Vec<String>,
+    /// Image shown in the larger view, `None` shows the thumbnails
+    selected: Option<usize>,
+}
+
+impl Gallery {
+    pub fn list<'a>(images: impl IntoIterator<Item = &'a str>) -> Self {
+        Self {
+            images: images.into_iter().map(String::from).collect(),
+            selected: None,
+        }
+    }
+
+    /// All images in an asset folder, in alphabetical order
+    pub fn folder(asset_server: &AssetServer, folder: &str) -> Self {
+        let mut images: Vec<String> =
+            match asset_server.asset_io().read_directory(Path::new(folder)) {
+                Ok(paths) => paths
+                    .filter(|path| {
+                        matches!(
+                            path.extension().and_then(|extension| extension.to_str()),
+                            Some("png" | "jpg" | "jpeg")
+                        )
+                    })
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .collect(),
+                Err(err) => {
+                    warn!("Can't list gallery folder {:?}: {:?}", folder, err);
+                    Vec::new()
+                }
+            };
+        images.sort();
+        Self {
+            images,
+            selected: None,
+        }
+    }
+
+    /// Paths of the listed images
+    pub fn images(&self) -> &[String] {
+        &self.images
+    }
+}
+
+/// Message key of the alt text of the image at `path`, e.g. `alt-kittens-kittens-png`
+pub fn alt_text_key(path: &str) -> String {
+    let path: String = path
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    format!("alt-{}", path)
+}
+
+/// Thumbnail button, the image is requested once the thumbnail becomes visible
+#[derive(Component)]
+struct GalleryThumbnail {
+    gallery: Entity,
+    index: usize,
+    requested: bool,
+}
+
+/// Larger view of one image, clicking it goes back to the thumbnails
+#[derive(Component)]
+struct GalleryLargeView {
+    gallery: Entity,
+}
+
+fn gallery_click_system(
+    thumbnails: Query<(&Interaction, &GalleryThumbnail), Changed<Interaction>>,
+    large_views: Query<(&Interaction, &GalleryLargeView), Changed<Interaction>>,
+    mut galleries: Query<&mut Gallery>,
+) {
+    for (interaction, thumbnail) in thumbnails.iter() {
+        if *interaction == Interaction::Clicked {
+            if let Ok(mut gallery) = galleries.get_mut(thumbnail.gallery) {
+                gallery.selected = Some(thumbnail.index);
+            }
+        }
+    }
+    for (interaction, large_view) in large_views.iter() {
+        if *interaction == Interaction::Clicked {
+            if let Ok(mut gallery) = galleries.get_mut(large_view.gallery) {
+                gallery.selected = None;
+            }
+        }
+    }
+}
+
+fn spawn_gallery(
+    mut commands: Commands,
+    galleries: Query<(Entity, &Gallery), Changed<Gallery>>,
+    asset_server: Res<AssetServer>,
+) {
+    for (e, gallery) in galleries.iter() {
+        commands.entity(e).despawn_descendants();
+
+        match gallery.selected.and_then(|index| gallery.images.get(index)) {
+            Some(path) => {
+                commands.entity(e).with_children(|parent| {
+                    // An `ImageBundle` rather than a `ButtonBundle` to get the size of the image
+                    parent
+                        .spawn_bundle(ImageBundle {
+                            image: asset_server.load(path.as_str()).into(),
+                            ..default()
+                        })
+                        .insert(Button)
+                        .insert(Interaction::None)
+                        .insert(GalleryLargeView { gallery: e })
+                        .insert(AccessibleRole::Image)
+                        .insert(LocalizedLabel(alt_text_key(path)));
+                });
+            }
+            None => {
+                commands.entity(e).with_children(|parent| {
+                    parent
+                        .spawn_bundle(NodeBundle {
+                            color: Color::NONE.into(),
+                            style: Style {
+                                flex_direction: FlexDirection::Row,
+                                // Like `ColumnReverse` elsewhere, makes the rows stack downwards
+                                flex_wrap: FlexWrap::WrapReverse,
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            for (index, path) in gallery.images.iter().enumerate() {
+                                parent
+                                    .spawn_bundle(ButtonBundle {
+                                        color: Color::GRAY.into(),
+                                        style: Style {
+                                            size: Size::new(
+                                                Val::Px(THUMBNAIL_SIZE),
+                                                Val::Px(THUMBNAIL_SIZE),
+                                            ),
+                                            margin: UiRect::all(Val::Px(5.0)),
+                                            ..default()
+                                        },
+                                        ..default()
+                                    })
+                                    .insert(GalleryThumbnail {
+                                        gallery: e,
+                                        index,
+                                        requested: false,
+                                    })
+                                    .insert(AccessibleRole::Image)
+                                    .insert(LocalizedLabel(alt_text_key(path)));
+                            }
+                        });
+                });
+            }
+        }
+    }
+}
+
+type ThumbnailQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut GalleryThumbnail,
+        &'static mut UiImage,
+        &'static mut UiColor,
+        &'static Node,
+        &'static GlobalTransform,
+        Option<&'static CalculatedClip>,
+    ),
+>;
+
+fn load_visible_thumbnails(
+    mut thumbnails: ThumbnailQuery,
+    galleries: Query<&Gallery>,
+    asset_server: Res<AssetServer>,
+    windows: Res<Windows>,
+) {
+    for (mut thumbnail, mut image, mut color, node, transform, clip) in thumbnails.iter_mut() {
+        if thumbnail.requested || !node_visible(node, transform, clip, &windows) {
+            continue;
+        }
+        let path = match galleries
+            .get(thumbnail.gallery)
+            .ok()
+            .and_then(|gallery| gallery.images.get(thumbnail.index))
+        {
+            Some(path) => path,
+            None => continue,
+        };
+        *image = asset_server.load(path.as_str()).into();
+        *color = Color::WHITE.into();
+        thumbnail.requested = true;
+    }
+}
@@ -0,0 +1,66 @@
+//! Cursor hit testing for ui nodes. `Interaction` can't be used to find out whether a container
+//! is hovered, since any child with the default `FocusPolicy::Block` swallows it.
+
+use bevy::{prelude::*, ui::CalculatedClip};
+
+/// Topmost ancestor of `entity`
+pub fn ui_tree_root(mut entity: Entity, parents: &Query<&Parent>) -> Entity {
+    while let Ok(parent) = parents.get(entity) {
+        entity = parent.get();
+    }
+    entity
+}
+
+pub fn cursor_position(windows: &Windows) -> Option<Vec2> {
+    windows
+        .get_primary()
+        .and_then(|window| window.cursor_position())
+}
+
+/// Min and max corner of the laid out, and optionally clipped, node
+pub fn node_rect(
+    node: &Node,
+    transform: &GlobalTransform,
+    clip: Option<&CalculatedClip>,
+) -> (Vec2, Vec2) {
+    let position = transform.translation().truncate();
+    let extents = node.size / 2.0;
+    let mut min = position - extents;
+    let mut max = position + extents;
+    if let Some(clip) = clip {
+        min = min.max(clip.clip.min);
+        max = max.min(clip.clip.max);
+    }
+    (min, max)
+}
+
+/// Whether `point` lies within the laid out, and optionally clipped, rect of the node
+pub fn node_contains(
+    node: &Node,
+    transform: &GlobalTransform,
+    clip: Option<&CalculatedClip>,
+    point: Vec2,
+) -> bool {
+    let (min, max) = node_rect(node, transform, clip);
+    (min.x..max.x).contains(&point.x) && (min.y..max.y).contains(&point.y)
+}
+
+/// Whether any part of the node is laid out inside the primary window and not clipped away
+pub fn node_visible(
+    node: &Node,
+    transform: &GlobalTransform,
+    clip: Option<&CalculatedClip>,
+    windows: &Windows,
+) -> bool {
+    let window = match windows.get_primary() {
+        Some(window) => window,
+        None => return false,
+    };
+    let (min, max) = node_rect(node, transform, clip);
+    min.x < max.x
+        && min.y < max.y
+        && max.x > 0.0
+        && max.y > 0.0
+        && min.x < window.width()
+        && min.y < window.height()
+}
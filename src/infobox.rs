@@ -0,0 +1,553 @@
+//! The infobox of the examples, defined once as ui branches so that each runner of `branch` can
+//! build it
+
+use bevy::{
+    ecs::system::{EntityCommands, SystemParam, SystemParamItem},
+    prelude::*,
+};
+
+use crate::{
+    accessibility::AccessibleRole,
+    branch::{UiBranch, UiSlotRegistry},
+    builder::{node, slot, text},
+    facts::{spawn_fact_navigation, CatFacts, FactCatalogue, FactNavigation, FactText},
+    focus::{Focus, FocusActivated},
+    gallery::Gallery,
+    hover::{cursor_position, node_contains, ui_tree_root},
+    input_device::{gamepad_just_pressed, ButtonPrompt},
+    locale::{LocalizedLabel, LocalizedText},
+    panel::{spawn_resize_handles, DragHandle, PanelDrag, PanelGeometry},
+    scroll::{ScrollPositions, Scrollable},
+    ui,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, UiBranch)]
+#[ui_branch(slot = UiInfoboxAnimals, state = UiInfoboxState, field = animal_state)]
+#[ui_branch(context = InfoboxContext<'static, 'static>)]
+pub enum UiInfoboxAnimalsState {
+    #[ui_branch(build = spawn_ui_infobox_dogs)]
+    Dogs,
+    #[ui_branch(build = spawn_ui_infobox_cats)]
+    Cats,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, UiBranch)]
+#[ui_branch(slot = UiInfoboxCat, state = UiInfoboxState, field = animal_cats_state)]
+#[ui_branch(rebuild_on = cat_fact, context = InfoboxContext<'static, 'static>)]
+pub enum UiInfoboxCatsContentState {
+    #[ui_branch(build = spawn_ui_infobox_cats_kittens)]
+    Kittens,
+    #[ui_branch(build = spawn_ui_infobox_cats_facts)]
+    Facts,
+}
+
+/// State governing an infobox. A component on the infobox root, or kept for the root in a
+/// `UiSlotRegistry`, depending on the runner.
+#[derive(Component, Clone, Debug, PartialEq, Eq)]
+pub struct UiInfoboxState {
+    pub visibility: bool,
+    pub animal_state: UiInfoboxAnimalsState,
+    pub animal_cats_state: UiInfoboxCatsContentState,
+    /// Index into the cat facts catalogue
+    pub cat_fact: usize,
+}
+
+impl Default for UiInfoboxState {
+    fn default() -> Self {
+        Self {
+            visibility: true,
+            animal_state: UiInfoboxAnimalsState::Cats,
+            animal_cats_state: UiInfoboxCatsContentState::Kittens,
+            cat_fact: 0,
+        }
+    }
+}
+
+impl UiInfoboxState {
+    /// Middle click toggles the visibility, left click shows dogs, and right click shows cats or
+    /// cycles their content if they're already shown
+    pub fn click(&mut self, button: MouseButton) {
+        match button {
+            MouseButton::Middle => {
+                info!("Toggle visibility");
+                self.visibility = !self.visibility;
+            }
+            MouseButton::Left => self.show_dogs(),
+            MouseButton::Right => {
+                if self.animal_state == UiInfoboxAnimalsState::Cats {
+                    self.cycle_cat_content();
+                } else {
+                    self.show_cats();
+                }
+            }
+            MouseButton::Other(_) => {}
+        }
+    }
+
+    /// Shoulder buttons switch between dogs and cats, the south face button cycles the cat
+    /// content
+    pub fn press(&mut self, button: GamepadButtonType) {
+        match button {
+            GamepadButtonType::RightTrigger => self.show_dogs(),
+            GamepadButtonType::LeftTrigger => self.show_cats(),
+            GamepadButtonType::South if self.animal_state == UiInfoboxAnimalsState::Cats => {
+                self.cycle_cat_content();
+            }
+            _ => {}
+        }
+    }
+
+    /// Moves through the cat facts of `catalogue`
+    pub fn navigate_facts(&mut self, catalogue: &FactCatalogue, navigation: FactNavigation) {
+        self.cat_fact = catalogue.navigate(self.cat_fact, navigation);
+        info!("Will show cat fact {}", self.cat_fact);
+    }
+
+    fn show_dogs(&mut self) {
+        info!("Will show dogs");
+        self.animal_state = UiInfoboxAnimalsState::Dogs;
+    }
+
+    fn show_cats(&mut self) {
+        info!("Will show cats");
+        self.animal_state = UiInfoboxAnimalsState::Cats;
+    }
+
+    fn cycle_cat_content(&mut self) {
+        if self.animal_cats_state == UiInfoboxCatsContentState::Kittens {
+            info!("Will show cats facts");
+            self.animal_cats_state = UiInfoboxCatsContentState::Facts;
+        } else {
+            info!("Will show cat kittens");
+            self.animal_cats_state = UiInfoboxCatsContentState::Kittens;
+        }
+    }
+}
+
+/// An infobox instance, e.g. one per selected unit. The root entity is the slot of
+/// `UiInfoboxPanel` and carries the `PanelGeometry` of the infobox.
+#[derive(Component)]
+pub struct UiInfoboxRoot;
+#[derive(Component)]
+pub struct UiInfoboxAnimals;
+#[derive(Component)]
+pub struct UiInfoboxCat;
+
+/// Frames since the last state change, for logging how long the ui takes to catch up
+pub struct FrameCounter(pub u32);
+
+/// Mouse clicks and focus activations of the frame which are meant for the infoboxes
+#[derive(SystemParam)]
+pub struct InfoboxClicks<'w, 's> {
+    mouse_button_input: Res<'w, Input<MouseButton>>,
+    windows: Res<'w, Windows>,
+    activations: EventReader<'w, 's, FocusActivated>,
+    ui_roots: Query<'w, 's, (Entity, &'static Node, &'static GlobalTransform), With<UiInfoboxRoot>>,
+    buttons: Query<'w, 's, &'static Interaction, With<Button>>,
+    panel_drag: Res<'w, PanelDrag>,
+    scroll: Res<'w, ScrollPositions>,
+}
+
+impl<'w, 's> InfoboxClicks<'w, 's> {
+    /// The clicked infoboxes, `None` for all of them, with the button to click them with.
+    /// Middle click toggles all infoboxes, left/right click only affects the hovered one. Enter
+    /// and Space on a focused infobox act like a left and right click on it.
+    pub fn read(&mut self) -> Vec<(Option<Entity>, MouseButton)> {
+        // Clicks on buttons inside the infobox are handled by the buttons themselves, and
+        // grabbing the panel to move or resize it or its content to scroll it isn't a click
+        // either
+        if self.panel_drag.is_active()
+            || self.scroll.is_dragging()
+            || self
+                .buttons
+                .iter()
+                .any(|interaction| *interaction == Interaction::Clicked)
+        {
+            return Vec::new();
+        }
+
+        let hovered = cursor_position(&self.windows).and_then(|cursor| {
+            self.ui_roots
+                .iter()
+                .find(|(_, node, transform)| node_contains(node, transform, None, cursor))
+                .map(|(e, _, _)| e)
+        });
+        let mouse_button_input = &self.mouse_button_input;
+        // Pressing the left button on scrollable content may start a drag, so it's only a click
+        // once it's let go without moving
+        let left_clicked = !self.scroll.is_grabbed()
+            && (mouse_button_input.just_pressed(MouseButton::Left) || self.scroll.was_clicked());
+        let mut clicks: Vec<(Option<Entity>, MouseButton)> = Vec::new();
+        if mouse_button_input.just_pressed(MouseButton::Middle) {
+            clicks.push((None, MouseButton::Middle));
+        }
+        if let Some(hovered) = hovered {
+            if left_clicked {
+                clicks.push((Some(hovered), MouseButton::Left));
+            }
+            if mouse_button_input.just_pressed(MouseButton::Right) {
+                clicks.push((Some(hovered), MouseButton::Right));
+            }
+        }
+        clicks.extend(
+            self.activations
+                .iter()
+                .map(|activation| (Some(activation.entity), activation.button)),
+        );
+        clicks
+    }
+}
+
+/// Clicks the `UiInfoboxState` components of the infobox roots, for the components approaches
+pub fn mouse_click_system(
+    mut clicks: InfoboxClicks,
+    mut ui_roots: Query<(Entity, &mut UiInfoboxState), With<UiInfoboxRoot>>,
+    mut frame_counter: ResMut<FrameCounter>,
+) {
+    frame_counter.0 += 1;
+    for (clicked, button) in clicks.read() {
+        for (e, mut ui_infobox_state) in ui_roots.iter_mut() {
+            if clicked.is_none_or(|clicked| clicked == e) {
+                ui_infobox_state.click(button);
+                frame_counter.0 = 0;
+            }
+        }
+    }
+}
+
+/// Clicks the infobox states kept in the `UiSlotRegistry`, for the resources approach
+pub fn registry_mouse_click_system(
+    mut clicks: InfoboxClicks,
+    mut registry: ResMut<UiSlotRegistry<UiInfoboxState>>,
+    mut frame_counter: ResMut<FrameCounter>,
+) {
+    frame_counter.0 += 1;
+    for (clicked, button) in clicks.read() {
+        for (e, ui_infobox_state) in registry.states_mut() {
+            if clicked.is_none_or(|clicked| clicked == e) {
+                ui_infobox_state.click(button);
+                frame_counter.0 = 0;
+            }
+        }
+    }
+}
+
+/// Gamepad buttons of the frame which are meant for the infoboxes
+#[derive(SystemParam)]
+pub struct InfoboxGamepad<'w, 's> {
+    gamepads: Res<'w, Gamepads>,
+    gamepad_buttons: Res<'w, Input<GamepadButton>>,
+    focus: Res<'w, Focus>,
+    parents: Query<'w, 's, &'static Parent>,
+}
+
+impl<'w, 's> InfoboxGamepad<'w, 's> {
+    /// The pressed buttons with the infobox they're meant for: the one with keyboard focus, or
+    /// `None` for all of them if nothing has focus
+    pub fn read(&self) -> Vec<(Option<Entity>, GamepadButtonType)> {
+        let focused_root = self.focus.entity.map(|e| ui_tree_root(e, &self.parents));
+        [
+            GamepadButtonType::RightTrigger,
+            GamepadButtonType::LeftTrigger,
+            GamepadButtonType::South,
+        ]
+        .into_iter()
+        .filter(|button| gamepad_just_pressed(&self.gamepads, &self.gamepad_buttons, *button))
+        .map(|button| (focused_root, button))
+        .collect()
+    }
+}
+
+/// Presses gamepad buttons on the `UiInfoboxState` components of the infobox roots, for the
+/// components approaches
+pub fn gamepad_system(
+    gamepad: InfoboxGamepad,
+    mut ui_roots: Query<(Entity, &mut UiInfoboxState), With<UiInfoboxRoot>>,
+    mut frame_counter: ResMut<FrameCounter>,
+) {
+    for (pressed, button) in gamepad.read() {
+        for (e, mut ui_infobox_state) in ui_roots.iter_mut() {
+            if pressed.is_none_or(|pressed| pressed == e) {
+                ui_infobox_state.press(button);
+                frame_counter.0 = 0;
+            }
+        }
+    }
+}
+
+/// Presses gamepad buttons on the infobox states kept in the `UiSlotRegistry`, for the resources
+/// approach
+pub fn registry_gamepad_system(
+    gamepad: InfoboxGamepad,
+    mut registry: ResMut<UiSlotRegistry<UiInfoboxState>>,
+    mut frame_counter: ResMut<FrameCounter>,
+) {
+    for (pressed, button) in gamepad.read() {
+        for (e, ui_infobox_state) in registry.states_mut() {
+            if pressed.is_none_or(|pressed| pressed == e) {
+                ui_infobox_state.press(button);
+                frame_counter.0 = 0;
+            }
+        }
+    }
+}
+
+/// Clicks on the fact navigation buttons of the infoboxes
+#[derive(SystemParam)]
+pub struct InfoboxFactNavigation<'w, 's> {
+    buttons: Query<
+        'w,
+        's,
+        (Entity, &'static Interaction, &'static FactNavigation),
+        Changed<Interaction>,
+    >,
+    parents: Query<'w, 's, &'static Parent>,
+    cat_facts: Res<'w, CatFacts>,
+    catalogues: Res<'w, Assets<FactCatalogue>>,
+}
+
+impl<'w, 's> InfoboxFactNavigation<'w, 's> {
+    /// The roots of the infoboxes whose buttons were clicked, with the catalogue to navigate.
+    /// Empty until the catalogue is loaded.
+    pub fn read(&self) -> Vec<(Entity, &FactCatalogue, FactNavigation)> {
+        let catalogue = match self.catalogues.get(&self.cat_facts.0) {
+            Some(catalogue) => catalogue,
+            None => return Vec::new(),
+        };
+        self.buttons
+            .iter()
+            .filter(|(_, interaction, _)| **interaction == Interaction::Clicked)
+            .map(|(e, _, navigation)| (ui_tree_root(e, &self.parents), catalogue, *navigation))
+            .collect()
+    }
+}
+
+/// Navigates the facts of the `UiInfoboxState` components of the infobox roots, for the
+/// components approaches
+pub fn fact_navigation_system(
+    navigation: InfoboxFactNavigation,
+    mut ui_roots: Query<&mut UiInfoboxState, With<UiInfoboxRoot>>,
+    mut frame_counter: ResMut<FrameCounter>,
+) {
+    for (root, catalogue, navigation) in navigation.read() {
+        if let Ok(mut ui_infobox_state) = ui_roots.get_mut(root) {
+            ui_infobox_state.navigate_facts(catalogue, navigation);
+            frame_counter.0 = 0;
+        }
+    }
+}
+
+/// Navigates the facts of the infobox states kept in the `UiSlotRegistry`, for the resources
+/// approach
+pub fn registry_fact_navigation_system(
+    navigation: InfoboxFactNavigation,
+    mut registry: ResMut<UiSlotRegistry<UiInfoboxState>>,
+    mut frame_counter: ResMut<FrameCounter>,
+) {
+    for (root, catalogue, navigation) in navigation.read() {
+        if let Some(ui_infobox_state) = registry.state_mut(root) {
+            ui_infobox_state.navigate_facts(catalogue, navigation);
+            frame_counter.0 = 0;
+        }
+    }
+}
+
+/// System params shared by the infobox branches
+#[derive(SystemParam)]
+pub struct InfoboxContext<'w, 's> {
+    asset_server: Res<'w, AssetServer>,
+    frame_counter: Res<'w, FrameCounter>,
+    geometries: Query<'w, 's, &'static PanelGeometry>,
+}
+
+/// The infobox panel, built into the root
+pub struct UiInfoboxPanel;
+
+impl UiBranch for UiInfoboxPanel {
+    type Slot = UiInfoboxRoot;
+    type State = UiInfoboxState;
+    type Context = InfoboxContext<'static, 'static>;
+
+    fn build(
+        entity: &mut EntityCommands,
+        state: &UiInfoboxState,
+        context: &mut SystemParamItem<Self::Context>,
+    ) {
+        let e = entity.id();
+        let geometry = match context.geometries.get(e) {
+            Ok(geometry) => *geometry,
+            Err(_) => return,
+        };
+        let display = if state.visibility {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        entity.insert_bundle(NodeBundle {
+            color: Color::RED.into(),
+            style: Style {
+                display,
+                size: geometry.ui_size(),
+                position_type: PositionType::Absolute,
+                align_items: AlignItems::FlexStart,
+                flex_direction: FlexDirection::ColumnReverse,
+                position: geometry.ui_position(),
+                ..default()
+            },
+            visibility: Visibility {
+                is_visible: state.visibility,
+            },
+            ..default()
+        });
+
+        if state.visibility {
+            info!("Frame: {:?} infobox root", context.frame_counter.0);
+            // Add children some of which can be stateful
+            ui!(entity => {
+                [
+                    text(context.asset_server.load("font.ttf"), 32.0, Color::BLUE),
+                    LocalizedText("infobox-title"),
+                    AccessibleRole::Heading,
+                    DragHandle { panel: e },
+                ];
+                [slot(), UiInfoboxAnimals];
+                [
+                    text(context.asset_server.load("font.ttf"), 16.0, Color::WHITE),
+                    ButtonPrompt {
+                        mouse: "prompt-mouse",
+                        keyboard: "prompt-keyboard",
+                        gamepad: "prompt-gamepad",
+                    },
+                    AccessibleRole::Text,
+                ];
+                with(|parent| spawn_resize_handles(parent, e));
+            });
+        }
+    }
+
+    /// The nested branches follow the rest of the state
+    fn changed(old: &UiInfoboxState, new: &UiInfoboxState) -> bool {
+        old.visibility != new.visibility
+    }
+}
+
+fn spawn_ui_infobox_cats(
+    entity: &mut EntityCommands,
+    _state: &UiInfoboxState,
+    context: &mut InfoboxContext,
+) {
+    ui!(entity => [
+        node(
+            Color::ORANGE,
+            Style {
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::FlexStart,
+                size: Size::new(Val::Percent(100.0), Val::Px(300.0)),
+                ..default()
+            },
+        ),
+        AccessibleRole::Tab,
+    ] {
+        //Should spawn cat stuff
+        [
+            text(context.asset_server.load("font.ttf"), 20.0, Color::BLUE),
+            LocalizedText("infobox-cats"),
+        ];
+        [slot(), UiInfoboxCat];
+    });
+}
+
+fn spawn_ui_infobox_cats_facts(
+    entity: &mut EntityCommands,
+    state: &UiInfoboxState,
+    context: &mut InfoboxContext,
+) {
+    ui!(entity => [
+        node(
+            Color::GREEN,
+            Style {
+                flex_direction: FlexDirection::ColumnReverse,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                overflow: Overflow::Hidden,
+                ..default()
+            },
+        ),
+        Scrollable {
+            branch: "cat_facts",
+        },
+        AccessibleRole::Tab,
+        LocalizedLabel("infobox-facts".to_owned()),
+    ] {
+        //Should spawn cat facts
+        [
+            text(context.asset_server.load("font.ttf"), 20.0, Color::BLUE),
+            FactText {
+                index: state.cat_fact,
+            },
+            AccessibleRole::Text,
+        ];
+        with(|parent| spawn_fact_navigation(parent, context.asset_server.load("font.ttf")));
+    });
+}
+
+fn spawn_ui_infobox_cats_kittens(
+    entity: &mut EntityCommands,
+    _state: &UiInfoboxState,
+    context: &mut InfoboxContext,
+) {
+    info!("Frame: {:?} infobox kittens", context.frame_counter.0);
+    ui!(entity => [
+        node(
+            Color::GREEN,
+            Style {
+                flex_direction: FlexDirection::ColumnReverse,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                overflow: Overflow::Hidden,
+                ..default()
+            },
+        ),
+        Scrollable {
+            branch: "cat_kittens",
+        },
+        AccessibleRole::Tab,
+        LocalizedLabel("infobox-kittens".to_owned()),
+    ] {
+        //Should spawn kittens
+        [
+            node(Color::NONE, Style::default()),
+            Gallery::folder(&context.asset_server, "kittens"),
+        ];
+    });
+}
+
+///  Dog stuff
+fn spawn_ui_infobox_dogs(
+    entity: &mut EntityCommands,
+    _state: &UiInfoboxState,
+    context: &mut InfoboxContext,
+) {
+    ui!(entity => [
+        node(
+            Color::ORANGE,
+            Style {
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::FlexStart,
+                size: Size::new(Val::Percent(100.0), Val::Px(300.0)),
+                overflow: Overflow::Hidden,
+                ..default()
+            },
+        ),
+        Scrollable { branch: "dogs" },
+        AccessibleRole::Tab,
+    ] {
+        //Should spawn dog stuff
+        [
+            text(context.asset_server.load("font.ttf"), 20.0, Color::YELLOW),
+            LocalizedText("infobox-dogs"),
+        ];
+        [
+            node(Color::NONE, Style::default()),
+            Gallery::list(["dog.png"]),
+        ];
+    });
+}
@@ -0,0 +1,89 @@
+//! Tracks the input device used last, so button prompts can show the buttons of that device
+
+use bevy::{input::mouse::MouseWheel, prelude::*};
+
+use crate::locale::LocalizedText;
+
+pub struct InputDevicePlugin;
+
+impl Plugin for InputDevicePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputDevice>()
+            .add_system(detect_input_device)
+            .add_system(update_button_prompts.after(detect_input_device));
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InputDevice {
+    #[default]
+    Mouse,
+    Keyboard,
+    Gamepad,
+}
+
+/// Text node showing one of the message keys, depending on the active input device
+#[derive(Component)]
+pub struct ButtonPrompt {
+    pub mouse: &'static str,
+    pub keyboard: &'static str,
+    pub gamepad: &'static str,
+}
+
+impl ButtonPrompt {
+    fn key(&self, device: InputDevice) -> &'static str {
+        match device {
+            InputDevice::Mouse => self.mouse,
+            InputDevice::Keyboard => self.keyboard,
+            InputDevice::Gamepad => self.gamepad,
+        }
+    }
+}
+
+/// Whether `button` was just pressed on any connected gamepad
+pub fn gamepad_just_pressed(
+    gamepads: &Gamepads,
+    gamepad_buttons: &Input<GamepadButton>,
+    button: GamepadButtonType,
+) -> bool {
+    gamepads
+        .iter()
+        .any(|gamepad| gamepad_buttons.just_pressed(GamepadButton::new(*gamepad, button)))
+}
+
+fn detect_input_device(
+    mut device: ResMut<InputDevice>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+) {
+    let scrolled = mouse_wheel_events.iter().count() > 0;
+    let used = if gamepad_buttons.get_just_pressed().next().is_some() {
+        InputDevice::Gamepad
+    } else if keyboard_input.get_just_pressed().next().is_some() {
+        InputDevice::Keyboard
+    } else if mouse_button_input.get_just_pressed().next().is_some() || scrolled {
+        InputDevice::Mouse
+    } else {
+        return;
+    };
+    if *device != used {
+        info!("Switch input device to {:?}", used);
+        *device = used;
+    }
+}
+
+fn update_button_prompts(
+    mut commands: Commands,
+    device: Res<InputDevice>,
+    prompts: Query<(Entity, &ButtonPrompt, ChangeTrackers<ButtonPrompt>)>,
+) {
+    for (e, prompt, tracker) in prompts.iter() {
+        if device.is_changed() || tracker.is_added() {
+            commands
+                .entity(e)
+                .insert(LocalizedText(prompt.key(*device)));
+        }
+    }
+}
@@ -0,0 +1,31 @@
+//! Helpers for systems feeding the ui layout, which is recomputed whenever one of its inputs
+//! changes
+
+use std::ops::DerefMut;
+
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    window::{WindowResized, WindowScaleFactorChanged},
+};
+
+/// Assigns `value` only if it differs, since every change triggers a new layout
+pub fn set_if_changed<T: PartialEq>(target: &mut impl DerefMut<Target = T>, value: T) {
+    if **target != value {
+        **target = value;
+    }
+}
+
+/// Resizes and scale factor changes of the windows, both change their logical size
+#[derive(SystemParam)]
+pub struct WindowChanges<'w, 's> {
+    resized_events: EventReader<'w, 's, WindowResized>,
+    scale_factor_events: EventReader<'w, 's, WindowScaleFactorChanged>,
+}
+
+impl<'w, 's> WindowChanges<'w, 's> {
+    /// Whether a window changed its logical size since the last call
+    pub fn changed(&mut self) -> bool {
+        self.resized_events.iter().count() + self.scale_factor_events.iter().count() > 0
+    }
+}
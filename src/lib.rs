@@ -0,0 +1,24 @@
+//! Building blocks shared by the ui pattern examples
+
+// Lets `#[derive(UiBranch)]` refer to this crate by name from within it
+extern crate self as bevy_ui_pattern_example;
+
+pub mod accessibility;
+pub mod branch;
+pub mod builder;
+pub mod facts;
+pub mod focus;
+pub mod gallery;
+pub mod hover;
+pub mod infobox;
+pub mod input_device;
+pub mod layout;
+pub mod locale;
+pub mod panel;
+pub mod placement;
+pub mod replay;
+pub mod responsive;
+pub mod rich_text;
+pub mod screenshot;
+pub mod scroll;
+pub mod snapshot;
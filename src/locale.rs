@@ -0,0 +1,193 @@
+//! Localization of ui texts. Messages are read from `assets/locales/<locale>/infobox.ftl`, which
+//! supports the plain message subset of Fluent: `key = value`, indented continuation lines and
+//! `#` comments. Switching the `Locale` updates every `LocalizedText` in place.
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::{BoxedFuture, HashMap},
+};
+
+use crate::{
+    accessibility::AccessibleLabel,
+    rich_text::{set_rich_text, RichTextFonts},
+};
+
+/// Locales with a folder under `assets/locales`, `L` cycles through them
+pub const LOCALES: &[&str] = &["en-US", "sv-SE"];
+
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Locale>()
+            .init_resource::<RichTextFonts>()
+            .add_asset::<Translations>()
+            .init_asset_loader::<TranslationsLoader>()
+            .add_startup_system(load_translations)
+            .add_system(cycle_locale_system)
+            .add_system(reload_translations.after(cycle_locale_system))
+            .add_system(update_localized_texts.after(reload_translations))
+            .add_system(update_localized_labels.after(reload_translations));
+    }
+}
+
+pub struct Locale(pub String);
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self(LOCALES[0].to_owned())
+    }
+}
+
+impl Locale {
+    /// Path of a localized asset
+    pub fn asset_path(&self, file: &str) -> String {
+        format!("locales/{}/{}", self.0, file)
+    }
+}
+
+#[derive(TypeUuid)]
+#[uuid = "41b3f3d7-ff2f-481a-89f7-48cbb09c6fb7"]
+pub struct Translations {
+    messages: HashMap<String, String>,
+}
+
+impl Translations {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.messages.get(key).map(String::as_str)
+    }
+
+    fn parse(source: &str) -> Self {
+        let mut messages = HashMap::default();
+        let mut current: Option<(String, String)> = None;
+        for line in source.lines() {
+            let is_continuation = line.starts_with(char::is_whitespace) && !line.trim().is_empty();
+            match current.as_mut() {
+                Some((_, value)) if is_continuation => {
+                    if !value.is_empty() {
+                        value.push('\n');
+                    }
+                    value.push_str(line.trim());
+                    continue;
+                }
+                _ => {}
+            }
+            if let Some((key, value)) = current.take() {
+                messages.insert(key, value);
+            }
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.split_once('=') {
+                Some((key, value)) => {
+                    current = Some((key.trim().to_owned(), value.trim().to_owned()));
+                }
+                None => warn!("Ignoring malformed translation line {:?}", line),
+            }
+        }
+        if let Some((key, value)) = current {
+            messages.insert(key, value);
+        }
+        Self { messages }
+    }
+}
+
+#[derive(Default)]
+struct TranslationsLoader;
+
+impl AssetLoader for TranslationsLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let translations = Translations::parse(std::str::from_utf8(bytes)?);
+            load_context.set_default_asset(LoadedAsset::new(translations));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ftl"]
+    }
+}
+
+/// Handle to the translations of the current locale
+pub struct CurrentTranslations(pub Handle<Translations>);
+
+/// Text node showing the message `key` of the current locale, messages may use rich text markup
+#[derive(Component)]
+pub struct LocalizedText(pub &'static str);
+
+/// Sets the `AccessibleLabel` of the node to the message `key` of the current locale
+#[derive(Component)]
+pub struct LocalizedLabel(pub String);
+
+fn load_translations(mut commands: Commands, asset_server: Res<AssetServer>, locale: Res<Locale>) {
+    commands.insert_resource(CurrentTranslations(
+        asset_server.load(&locale.asset_path("infobox.ftl")),
+    ));
+}
+
+fn cycle_locale_system(keyboard_input: Res<Input<KeyCode>>, mut locale: ResMut<Locale>) {
+    if keyboard_input.just_pressed(KeyCode::L) {
+        let current = LOCALES.iter().position(|l| *l == locale.0).unwrap_or(0);
+        locale.0 = LOCALES[(current + 1) % LOCALES.len()].to_owned();
+        info!("Switch locale to {}", locale.0);
+    }
+}
+
+fn reload_translations(
+    mut current: ResMut<CurrentTranslations>,
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+) {
+    if locale.is_changed() && !locale.is_added() {
+        current.0 = asset_server.load(&locale.asset_path("infobox.ftl"));
+    }
+}
+
+fn update_localized_texts(
+    mut events: EventReader<AssetEvent<Translations>>,
+    current: Res<CurrentTranslations>,
+    translations: Res<Assets<Translations>>,
+    fonts: Res<RichTextFonts>,
+    mut texts: Query<(&LocalizedText, &mut Text, ChangeTrackers<LocalizedText>)>,
+) {
+    // A previously loaded locale is available right away and sends no event
+    let reloaded = events.iter().count() > 0 || current.is_changed();
+    let translations = match translations.get(&current.0) {
+        Some(translations) => translations,
+        None => return,
+    };
+    for (localized, mut text, tracker) in texts.iter_mut() {
+        if reloaded || tracker.is_changed() {
+            let markup = translations.get(localized.0).unwrap_or(localized.0);
+            set_rich_text(&mut text, markup, &fonts);
+        }
+    }
+}
+
+fn update_localized_labels(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<Translations>>,
+    current: Res<CurrentTranslations>,
+    translations: Res<Assets<Translations>>,
+    labels: Query<(Entity, &LocalizedLabel, ChangeTrackers<LocalizedLabel>)>,
+) {
+    let reloaded = events.iter().count() > 0 || current.is_changed();
+    let translations = match translations.get(&current.0) {
+        Some(translations) => translations,
+        None => return,
+    };
+    for (e, localized, tracker) in labels.iter() {
+        // Re-inserting the label on a reused node only marks it as changed
+        if reloaded || tracker.is_changed() {
+            let label = translations.get(&localized.0).unwrap_or(&localized.0);
+            commands.entity(e).insert(AccessibleLabel(label.to_owned()));
+        }
+    }
+}
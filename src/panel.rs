@@ -0,0 +1,266 @@
+//! Panels which can be dragged by a handle, e.g. their title, and resized from their corners.
+//! The geometry lives in a `PanelGeometry` component on the panel, so it survives re-renders.
+
+use bevy::{
+    prelude::*,
+    ui::{CalculatedClip, UiSystem},
+};
+
+use crate::hover::{cursor_position, node_contains};
+
+const RESIZE_HANDLE_SIZE: f32 = 12.0;
+const RESIZE_HANDLE_COLOR: Color = Color::rgba(1.0, 1.0, 1.0, 0.5);
+
+pub struct PanelPlugin;
+
+impl Plugin for PanelPlugin {
+    fn build(&self, app: &mut App) {
+        // Grabs are known before `Update`, so click handlers can ignore them
+        app.init_resource::<PanelDrag>()
+            .add_system_to_stage(CoreStage::PreUpdate, drag_panels.after(UiSystem::Focus))
+            // Late, so geometry changes of the whole update stage make it into this frame's layout
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                apply_panel_geometry.before(UiSystem::Flex),
+            );
+    }
+}
+
+/// Position and size of an absolutely positioned panel, in logical pixels
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct PanelGeometry {
+    /// Offset of the top-left corner from the top-left corner of the window
+    pub position: Vec2,
+    pub size: Vec2,
+    pub min_size: Vec2,
+    pub max_size: Vec2,
+}
+
+impl PanelGeometry {
+    pub fn new(position: Vec2, size: Vec2) -> Self {
+        Self {
+            position,
+            size,
+            min_size: Vec2::new(300.0, 200.0),
+            max_size: Vec2::new(1200.0, 900.0),
+        }
+    }
+
+    /// Style position of the panel. Ui layout is y-up in this bevy version, so the gap to the
+    /// top of the window is the `bottom` of the style.
+    pub fn ui_position(&self) -> UiRect<Val> {
+        UiRect {
+            left: Val::Px(self.position.x),
+            bottom: Val::Px(self.position.y),
+            ..default()
+        }
+    }
+
+    pub fn ui_size(&self) -> Size<Val> {
+        Size::new(Val::Px(self.size.x), Val::Px(self.size.y))
+    }
+
+    /// Moves `corner` by the cursor movement `delta`, keeping the opposite corner in place
+    fn resize(&mut self, corner: Corner, delta: Vec2) {
+        // Edges as distances from the left and top of the window
+        let mut left = self.position.x;
+        let mut right = self.position.x + self.size.x;
+        let mut top = self.position.y;
+        let mut bottom = self.position.y + self.size.y;
+        let (is_left, is_top) = match corner {
+            Corner::TopLeft => (true, true),
+            Corner::TopRight => (false, true),
+            Corner::BottomLeft => (true, false),
+            Corner::BottomRight => (false, false),
+        };
+        if is_left {
+            left += delta.x;
+        } else {
+            right += delta.x;
+        }
+        // The cursor is y-up
+        if is_top {
+            top -= delta.y;
+        } else {
+            bottom -= delta.y;
+        }
+
+        let size = Vec2::new(right - left, bottom - top).clamp(self.min_size, self.max_size);
+        if is_left {
+            left = right - size.x;
+        }
+        if is_top {
+            top = bottom - size.y;
+        }
+        self.position = Vec2::new(left, top);
+        self.size = size;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Node which moves `panel` when dragged
+#[derive(Component)]
+pub struct DragHandle {
+    pub panel: Entity,
+}
+
+#[derive(Component)]
+struct ResizeHandle {
+    panel: Entity,
+    corner: Corner,
+}
+
+struct Grab {
+    panel: Entity,
+    /// `None` moves the panel
+    corner: Option<Corner>,
+    cursor: Vec2,
+}
+
+/// The panel being dragged or resized
+#[derive(Default)]
+pub struct PanelDrag {
+    grab: Option<Grab>,
+}
+
+impl PanelDrag {
+    pub fn is_active(&self) -> bool {
+        self.grab.is_some()
+    }
+
+    pub fn panel(&self) -> Option<Entity> {
+        self.grab.as_ref().map(|grab| grab.panel)
+    }
+}
+
+/// Spawns the resize handles of `panel` into it
+pub fn spawn_resize_handles(parent: &mut ChildBuilder, panel: Entity) {
+    for corner in [
+        Corner::TopLeft,
+        Corner::TopRight,
+        Corner::BottomLeft,
+        Corner::BottomRight,
+    ] {
+        // Flipped like the panel position, `bottom` is the top of the panel
+        let position = match corner {
+            Corner::TopLeft => UiRect {
+                left: Val::Px(0.0),
+                bottom: Val::Px(0.0),
+                ..default()
+            },
+            Corner::TopRight => UiRect {
+                right: Val::Px(0.0),
+                bottom: Val::Px(0.0),
+                ..default()
+            },
+            Corner::BottomLeft => UiRect {
+                left: Val::Px(0.0),
+                top: Val::Px(0.0),
+                ..default()
+            },
+            Corner::BottomRight => UiRect {
+                right: Val::Px(0.0),
+                top: Val::Px(0.0),
+                ..default()
+            },
+        };
+        parent
+            .spawn_bundle(NodeBundle {
+                color: RESIZE_HANDLE_COLOR.into(),
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position,
+                    size: Size::new(Val::Px(RESIZE_HANDLE_SIZE), Val::Px(RESIZE_HANDLE_SIZE)),
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(ResizeHandle { panel, corner });
+    }
+}
+
+fn drag_panels(
+    mouse_button_input: Res<Input<MouseButton>>,
+    windows: Res<Windows>,
+    mut drag: ResMut<PanelDrag>,
+    drag_handles: Query<(
+        &DragHandle,
+        &Node,
+        &GlobalTransform,
+        Option<&CalculatedClip>,
+    )>,
+    resize_handles: Query<(
+        &ResizeHandle,
+        &Node,
+        &GlobalTransform,
+        Option<&CalculatedClip>,
+    )>,
+    mut panels: Query<&mut PanelGeometry>,
+) {
+    let cursor = match cursor_position(&windows) {
+        Some(cursor) if mouse_button_input.pressed(MouseButton::Left) => cursor,
+        _ => {
+            drag.grab = None;
+            return;
+        }
+    };
+
+    if mouse_button_input.just_pressed(MouseButton::Left) {
+        // Resize handles lie on top of the panel content, so they win
+        drag.grab = resize_handles
+            .iter()
+            .find(|(_, node, transform, clip)| node_contains(node, transform, *clip, cursor))
+            .map(|(handle, ..)| Grab {
+                panel: handle.panel,
+                corner: Some(handle.corner),
+                cursor,
+            })
+            .or_else(|| {
+                drag_handles
+                    .iter()
+                    .find(|(_, node, transform, clip)| {
+                        node_contains(node, transform, *clip, cursor)
+                    })
+                    .map(|(handle, ..)| Grab {
+                        panel: handle.panel,
+                        corner: None,
+                        cursor,
+                    })
+            });
+        return;
+    }
+
+    let (panel, corner, delta) = match drag.grab.as_mut() {
+        Some(grab) => {
+            let delta = cursor - grab.cursor;
+            grab.cursor = cursor;
+            (grab.panel, grab.corner, delta)
+        }
+        None => return,
+    };
+    if delta == Vec2::ZERO {
+        return;
+    }
+    match panels.get_mut(panel) {
+        Ok(mut geometry) => match corner {
+            // The cursor is y-up, the panel position y-down
+            None => geometry.position += Vec2::new(delta.x, -delta.y),
+            Some(corner) => geometry.resize(corner, delta),
+        },
+        Err(_) => drag.grab = None,
+    }
+}
+
+fn apply_panel_geometry(mut panels: Query<(&PanelGeometry, &mut Style), Changed<PanelGeometry>>) {
+    for (geometry, mut style) in panels.iter_mut() {
+        style.position = geometry.ui_position();
+        style.size = geometry.ui_size();
+    }
+}
@@ -0,0 +1,180 @@
+//! Placement of panels relative to the window: anchored to one of nine points, docked to an
+//! edge, or following an entity in the world. Moving a panel by hand keeps its placement kind and
+//! only changes the offset, except that a docked panel becomes anchored.
+
+use bevy::{core_pipeline::core_2d::Camera2d, prelude::*};
+
+use crate::{
+    layout::{set_if_changed, WindowChanges},
+    panel::{PanelDrag, PanelGeometry},
+};
+
+pub struct PlacementPlugin;
+
+impl Plugin for PlacementPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(place_panels);
+    }
+}
+
+/// Point of a panel or the window
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    CenterLeft,
+    Center,
+    CenterRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+}
+
+impl Anchor {
+    /// Position within a rect of `size`, from its top-left corner
+    fn point(self, size: Vec2) -> Vec2 {
+        let fraction = match self {
+            Anchor::TopLeft => Vec2::new(0.0, 0.0),
+            Anchor::TopCenter => Vec2::new(0.5, 0.0),
+            Anchor::TopRight => Vec2::new(1.0, 0.0),
+            Anchor::CenterLeft => Vec2::new(0.0, 0.5),
+            Anchor::Center => Vec2::new(0.5, 0.5),
+            Anchor::CenterRight => Vec2::new(1.0, 0.5),
+            Anchor::BottomLeft => Vec2::new(0.0, 1.0),
+            Anchor::BottomCenter => Vec2::new(0.5, 1.0),
+            Anchor::BottomRight => Vec2::new(1.0, 1.0),
+        };
+        fraction * size
+    }
+}
+
+/// Window edge a panel is docked to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dock {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// Where a panel is placed. Offsets are in logical pixels, x to the right and y downwards.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub enum Placement {
+    /// Point `anchor` of the panel sits at point `anchor` of the window, moved by `offset`
+    Anchored { anchor: Anchor, offset: Vec2 },
+    /// Stretched along an edge of the window, keeping its size across it
+    Docked(Dock),
+    /// Point `anchor` of the panel follows `entity` on screen, moved by `offset`
+    Following {
+        entity: Entity,
+        anchor: Anchor,
+        offset: Vec2,
+    },
+}
+
+impl Placement {
+    /// Geometry of `geometry` placed in a window of `window` size. `target` is the screen
+    /// position of the followed entity, if it is on screen.
+    fn place(&self, geometry: PanelGeometry, window: Vec2, target: Option<Vec2>) -> PanelGeometry {
+        let mut placed = geometry;
+        match *self {
+            Placement::Anchored { anchor, offset } => {
+                placed.position = anchor.point(window) - anchor.point(geometry.size) + offset;
+            }
+            Placement::Docked(dock) => {
+                let size = geometry.size;
+                (placed.position, placed.size) = match dock {
+                    Dock::Left => (Vec2::ZERO, Vec2::new(size.x, window.y)),
+                    Dock::Right => (
+                        Vec2::new(window.x - size.x, 0.0),
+                        Vec2::new(size.x, window.y),
+                    ),
+                    Dock::Top => (Vec2::ZERO, Vec2::new(window.x, size.y)),
+                    Dock::Bottom => (
+                        Vec2::new(0.0, window.y - size.y),
+                        Vec2::new(window.x, size.y),
+                    ),
+                };
+            }
+            Placement::Following { anchor, offset, .. } => {
+                if let Some(target) = target {
+                    placed.position = target - anchor.point(geometry.size) + offset;
+                }
+            }
+        }
+        placed
+    }
+
+    /// Placement which keeps a panel moved by hand to `geometry` where it is
+    fn adopt(&self, geometry: &PanelGeometry, window: Vec2, target: Option<Vec2>) -> Self {
+        match *self {
+            Placement::Anchored { anchor, .. } => Placement::Anchored {
+                anchor,
+                offset: geometry.position + anchor.point(geometry.size) - anchor.point(window),
+            },
+            Placement::Docked(_) => Placement::Anchored {
+                anchor: Anchor::TopLeft,
+                offset: geometry.position,
+            },
+            Placement::Following {
+                entity,
+                anchor,
+                offset,
+            } => Placement::Following {
+                entity,
+                anchor,
+                offset: match target {
+                    Some(target) => geometry.position + anchor.point(geometry.size) - target,
+                    None => offset,
+                },
+            },
+        }
+    }
+}
+
+fn place_panels(
+    mut window_changes: WindowChanges,
+    windows: Res<Windows>,
+    drag: Res<PanelDrag>,
+    cameras: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
+    transforms: Query<&GlobalTransform>,
+    mut panels: Query<(Entity, &mut Placement, &mut PanelGeometry)>,
+) {
+    let resized = window_changes.changed();
+    let window = match windows.get_primary() {
+        Some(window) => Vec2::new(window.width(), window.height()),
+        None => return,
+    };
+    let camera = cameras.iter().next();
+    // Viewport positions are y-up, panel positions y-down
+    let screen_position = |entity: Entity| {
+        let (camera, camera_transform) = camera?;
+        let world_position = transforms.get(entity).ok()?.translation();
+        let viewport_position = camera.world_to_viewport(camera_transform, world_position)?;
+        Some(Vec2::new(
+            viewport_position.x,
+            window.y - viewport_position.y,
+        ))
+    };
+
+    for (e, mut placement, mut geometry) in panels.iter_mut() {
+        let target = match *placement {
+            Placement::Following { entity, .. } => screen_position(entity),
+            _ => None,
+        };
+        if drag.panel() == Some(e) {
+            let adopted = placement.adopt(&geometry, window, target);
+            if *placement != adopted {
+                *placement = adopted;
+            }
+            continue;
+        }
+        let follows = matches!(*placement, Placement::Following { .. });
+        if !(resized || follows || placement.is_changed() || geometry.is_changed()) {
+            continue;
+        }
+        let placed = placement.place(*geometry, window, target);
+        set_if_changed(&mut geometry, placed);
+    }
+}
@@ -0,0 +1,192 @@
+//! Recording and replaying the mouse clicks the examples' click systems consume, so bug reports
+//! can ship a reproducible input script. Run an example with `UI_RECORD_INPUT=clicks.ron` to
+//! record its clicks into a file, and with `UI_REPLAY_INPUT=clicks.ron` to replay them.
+//!
+//! The buttons pressed in an update are keyed by the `FrameCounter` frame of that update. The
+//! counter restarts with every state change, so a replay stays in step with the ui regardless of
+//! how long loading took, as long as the state is only changed by the script. Keys repeat when
+//! clicks are evenly spaced, so the replay presses the buttons of at most one update per update.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::{input::InputSystem, math::DVec2, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::infobox::FrameCounter;
+
+pub struct InputScriptPlugin;
+
+impl Plugin for InputScriptPlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(script) = InputScript::from_env() {
+            app.insert_resource(script);
+        }
+        // After the input systems, so recorded clicks are this frame's and replayed ones aren't
+        // cleared before the click systems see them
+        app.add_system_to_stage(CoreStage::PreUpdate, run_input_script.after(InputSystem));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScriptButton {
+    Left,
+    Right,
+    Middle,
+    Other(u16),
+}
+
+impl From<MouseButton> for ScriptButton {
+    fn from(button: MouseButton) -> Self {
+        match button {
+            MouseButton::Left => Self::Left,
+            MouseButton::Right => Self::Right,
+            MouseButton::Middle => Self::Middle,
+            MouseButton::Other(button) => Self::Other(button),
+        }
+    }
+}
+
+impl From<ScriptButton> for MouseButton {
+    fn from(button: ScriptButton) -> Self {
+        match button {
+            ScriptButton::Left => Self::Left,
+            ScriptButton::Right => Self::Right,
+            ScriptButton::Middle => Self::Middle,
+            ScriptButton::Other(button) => Self::Other(button),
+        }
+    }
+}
+
+/// The mouse buttons pressed in one update
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScriptedClick {
+    /// `FrameCounter` value when the buttons were pressed
+    pub frame: u32,
+    pub buttons: Vec<ScriptButton>,
+    /// Cursor position in the primary window, in logical pixels
+    pub cursor: Option<(f32, f32)>,
+}
+
+pub enum InputScript {
+    /// Appends the clicks to the script at `path`, which is saved after every click
+    Record {
+        path: PathBuf,
+        clicks: Vec<ScriptedClick>,
+    },
+    /// Presses the buttons of `clicks` in turn, one click per update, and releases them the
+    /// next frame
+    Replay {
+        clicks: Vec<ScriptedClick>,
+        next: usize,
+        pressed: Vec<MouseButton>,
+    },
+}
+
+impl InputScript {
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self::Record {
+            path: path.into(),
+            clicks: Vec::new(),
+        }
+    }
+
+    pub fn replay(clicks: Vec<ScriptedClick>) -> Self {
+        Self::Replay {
+            clicks,
+            next: 0,
+            pressed: Vec::new(),
+        }
+    }
+
+    /// Replays the script at `path`
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let clicks = ron::de::from_str(&fs::read_to_string(path)?)?;
+        Ok(Self::replay(clicks))
+    }
+
+    /// Script given by the `UI_REPLAY_INPUT` or `UI_RECORD_INPUT` environment variable
+    pub fn from_env() -> Option<Self> {
+        if let Some(path) = std::env::var_os("UI_REPLAY_INPUT") {
+            match Self::load(Path::new(&path)) {
+                Ok(script) => return Some(script),
+                Err(err) => error!("Can't load input script {:?}: {:?}", path, err),
+            }
+        }
+        std::env::var_os("UI_RECORD_INPUT").map(Self::record)
+    }
+
+    /// Whether every click has been replayed. A recording is never finished.
+    pub fn is_finished(&self) -> bool {
+        match self {
+            Self::Record { .. } => false,
+            Self::Replay { clicks, next, .. } => *next >= clicks.len(),
+        }
+    }
+}
+
+fn save(path: &Path, clicks: &[ScriptedClick]) -> anyhow::Result<()> {
+    let script = ron::ser::to_string_pretty(clicks, ron::ser::PrettyConfig::default())?;
+    fs::write(path, script)?;
+    Ok(())
+}
+
+fn run_input_script(
+    script: Option<ResMut<InputScript>>,
+    frame_counter: Res<FrameCounter>,
+    mut mouse_button_input: ResMut<Input<MouseButton>>,
+    mut windows: ResMut<Windows>,
+) {
+    let mut script = match script {
+        Some(script) => script,
+        None => return,
+    };
+
+    match &mut *script {
+        InputScript::Record { path, clicks } => {
+            let cursor = windows
+                .get_primary()
+                .and_then(|window| window.cursor_position())
+                .map(|cursor| (cursor.x, cursor.y));
+            let buttons: Vec<ScriptButton> = mouse_button_input
+                .get_just_pressed()
+                .map(|button| (*button).into())
+                .collect();
+            if !buttons.is_empty() {
+                clicks.push(ScriptedClick {
+                    frame: frame_counter.0,
+                    buttons,
+                    cursor,
+                });
+                if let Err(err) = save(path, clicks) {
+                    error!("Can't save input script {:?}: {:?}", path, err);
+                }
+            }
+        }
+        InputScript::Replay {
+            clicks,
+            next,
+            pressed,
+        } => {
+            for button in pressed.drain(..) {
+                mouse_button_input.release(button);
+            }
+            let click = match clicks.get(*next) {
+                Some(click) if click.frame == frame_counter.0 => click,
+                _ => return,
+            };
+            if let (Some((x, y)), Some(window)) = (click.cursor, windows.get_primary_mut()) {
+                let physical = DVec2::new(x as f64, y as f64) * window.scale_factor();
+                window.update_cursor_physical_position_from_backend(Some(physical));
+            }
+            for button in &click.buttons {
+                let button = (*button).into();
+                mouse_button_input.press(button);
+                pressed.push(button);
+            }
+            *next += 1;
+        }
+    }
+}
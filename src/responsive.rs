@@ -0,0 +1,121 @@
+//! Breakpoints for responsive layouts. The breakpoint follows the logical width of the primary
+//! window, which changes with both the window size and its scale factor.
+
+use bevy::prelude::*;
+
+use crate::{
+    layout::{set_if_changed, WindowChanges},
+    panel::PanelGeometry,
+};
+
+pub struct ResponsivePlugin;
+
+impl Plugin for ResponsivePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Breakpoint>()
+            .add_system(update_breakpoint)
+            .add_system(layout_responsive_panels.after(update_breakpoint));
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Breakpoint {
+    Compact,
+    #[default]
+    Regular,
+    Wide,
+}
+
+impl Breakpoint {
+    /// Breakpoint of a window `width` logical pixels wide
+    pub fn from_width(width: f32) -> Self {
+        if width < 800.0 {
+            Self::Compact
+        } else if width < 1600.0 {
+            Self::Regular
+        } else {
+            Self::Wide
+        }
+    }
+}
+
+/// Panel size per breakpoint. Switching breakpoints resets the size of the panel, and the panel
+/// is kept inside the window as it is resized. Its other state is left alone.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct ResponsivePanel {
+    pub compact: Vec2,
+    pub regular: Vec2,
+    pub wide: Vec2,
+    /// Breakpoint the panel was last laid out for
+    applied: Option<Breakpoint>,
+}
+
+impl ResponsivePanel {
+    pub fn new(compact: Vec2, regular: Vec2, wide: Vec2) -> Self {
+        Self {
+            compact,
+            regular,
+            wide,
+            applied: None,
+        }
+    }
+
+    pub fn size(&self, breakpoint: Breakpoint) -> Vec2 {
+        match breakpoint {
+            Breakpoint::Compact => self.compact,
+            Breakpoint::Regular => self.regular,
+            Breakpoint::Wide => self.wide,
+        }
+    }
+}
+
+fn update_breakpoint(
+    mut window_changes: WindowChanges,
+    windows: Res<Windows>,
+    mut breakpoint: ResMut<Breakpoint>,
+) {
+    let resized = window_changes.changed();
+    if !resized && !breakpoint.is_added() {
+        return;
+    }
+    let window = match windows.get_primary() {
+        Some(window) => window,
+        None => return,
+    };
+    let new_breakpoint = Breakpoint::from_width(window.width());
+    if *breakpoint != new_breakpoint {
+        info!("Switch to {:?} layout", new_breakpoint);
+        *breakpoint = new_breakpoint;
+    }
+}
+
+fn layout_responsive_panels(
+    mut window_changes: WindowChanges,
+    windows: Res<Windows>,
+    breakpoint: Res<Breakpoint>,
+    mut panels: Query<(&mut ResponsivePanel, &mut PanelGeometry)>,
+) {
+    let resized = window_changes.changed();
+    let window = match windows.get_primary() {
+        Some(window) => Vec2::new(window.width(), window.height()),
+        None => return,
+    };
+    for (mut panel, mut geometry) in panels.iter_mut() {
+        let switched = panel.applied != Some(*breakpoint);
+        if !switched && !resized {
+            continue;
+        }
+        let mut new_geometry = *geometry;
+        if switched {
+            new_geometry.size = panel
+                .size(*breakpoint)
+                .clamp(geometry.min_size, geometry.max_size);
+            panel.applied = Some(*breakpoint);
+        }
+        new_geometry.size = new_geometry.size.min(window);
+        new_geometry.position = new_geometry
+            .position
+            .clamp(Vec2::ZERO, (window - new_geometry.size).max(Vec2::ZERO));
+        set_if_changed(&mut geometry, new_geometry);
+    }
+}
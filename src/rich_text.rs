@@ -0,0 +1,181 @@
+//! Lightweight markup for text nodes, parsed into one `TextSection` per span:
+//!
+//! - `*bold*` and `_italic_`, which can be nested
+//! - `[red]colored[/]` with a color name or a hex code like `[#ff8800]`
+//! - `:heart:` inline icons, see `RichTextFonts::icons`
+//! - `\` escapes the next character
+
+use bevy::{prelude::*, utils::HashMap};
+
+/// Fonts for the styled spans. The regular font is taken from the text node itself.
+pub struct RichTextFonts {
+    pub bold: Handle<Font>,
+    pub italic: Handle<Font>,
+    pub bold_italic: Handle<Font>,
+    /// Font the icon glyphs are taken from
+    pub icon_font: Handle<Font>,
+    /// Glyphs of the `:name:` icons
+    pub icons: HashMap<String, String>,
+}
+
+impl FromWorld for RichTextFonts {
+    fn from_world(world: &mut World) -> Self {
+        // The example only ships one font, insert this resource with other faces to use them
+        let font: Handle<Font> = world.resource::<AssetServer>().load("font.ttf");
+        let icons = [("heart", "♥"), ("star", "★"), ("arrow", "→")]
+            .into_iter()
+            .map(|(name, glyph)| (name.to_owned(), glyph.to_owned()))
+            .collect();
+        Self {
+            bold: font.clone(),
+            italic: font.clone(),
+            bold_italic: font.clone(),
+            icon_font: font,
+            icons,
+        }
+    }
+}
+
+/// Escapes `text` so it is shown as is
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '*' | '_' | '[' | ':' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Replaces the sections of `text` with the parsed `markup`. The style of the first section is
+/// the base style of the markup, it is always kept unstyled so the text can be set again.
+pub fn set_rich_text(text: &mut Text, markup: &str, fonts: &RichTextFonts) {
+    let base = match text.sections.first() {
+        Some(section) => section.style.clone(),
+        None => return,
+    };
+    text.sections = parse(markup, &base, fonts);
+}
+
+fn parse_color(name: &str) -> Option<Color> {
+    if let Some(hex) = name.strip_prefix('#') {
+        return Color::hex(hex).ok();
+    }
+    let color = match name {
+        "black" => Color::BLACK,
+        "white" => Color::WHITE,
+        "gray" => Color::GRAY,
+        "red" => Color::RED,
+        "green" => Color::GREEN,
+        "blue" => Color::BLUE,
+        "yellow" => Color::YELLOW,
+        "orange" => Color::ORANGE,
+        "pink" => Color::PINK,
+        "purple" => Color::PURPLE,
+        _ => return None,
+    };
+    Some(color)
+}
+
+struct Parser<'a> {
+    base: &'a TextStyle,
+    fonts: &'a RichTextFonts,
+    sections: Vec<TextSection>,
+    bold: bool,
+    italic: bool,
+    colors: Vec<Color>,
+}
+
+impl Parser<'_> {
+    fn style(&self) -> TextStyle {
+        let font = match (self.bold, self.italic) {
+            (false, false) => &self.base.font,
+            (true, false) => &self.fonts.bold,
+            (false, true) => &self.fonts.italic,
+            (true, true) => &self.fonts.bold_italic,
+        };
+        TextStyle {
+            font: font.clone(),
+            font_size: self.base.font_size,
+            color: self.colors.last().copied().unwrap_or(self.base.color),
+        }
+    }
+
+    fn push_str(&mut self, value: &str, style: TextStyle) {
+        match self.sections.last_mut() {
+            Some(section)
+                if section.style.font == style.font && section.style.color == style.color =>
+            {
+                section.value.push_str(value);
+            }
+            _ => self.sections.push(TextSection {
+                value: value.to_owned(),
+                style,
+            }),
+        }
+    }
+
+    fn push(&mut self, c: char) {
+        let style = self.style();
+        self.push_str(c.encode_utf8(&mut [0; 4]), style);
+    }
+}
+
+fn parse(markup: &str, base: &TextStyle, fonts: &RichTextFonts) -> Vec<TextSection> {
+    let mut parser = Parser {
+        base,
+        fonts,
+        sections: vec![TextSection {
+            value: String::new(),
+            style: base.clone(),
+        }],
+        bold: false,
+        italic: false,
+        colors: Vec::new(),
+    };
+    let mut rest = markup;
+    while let Some(c) = rest.chars().next() {
+        rest = &rest[c.len_utf8()..];
+        match c {
+            '\\' => {
+                if let Some(escaped) = rest.chars().next() {
+                    rest = &rest[escaped.len_utf8()..];
+                    parser.push(escaped);
+                }
+            }
+            '*' => parser.bold = !parser.bold,
+            '_' => parser.italic = !parser.italic,
+            '[' => match rest.split_once(']') {
+                Some(("/", after)) => {
+                    parser.colors.pop();
+                    rest = after;
+                }
+                Some((name, after)) => match parse_color(name) {
+                    Some(color) => {
+                        parser.colors.push(color);
+                        rest = after;
+                    }
+                    None => parser.push(c),
+                },
+                None => parser.push(c),
+            },
+            ':' => match rest
+                .split_once(':')
+                .and_then(|(name, after)| Some((fonts.icons.get(name)?, after)))
+            {
+                Some((glyph, after)) => {
+                    let style = TextStyle {
+                        font: fonts.icon_font.clone(),
+                        ..parser.style()
+                    };
+                    parser.push_str(glyph, style);
+                    rest = after;
+                }
+                None => parser.push(c),
+            },
+            _ => parser.push(c),
+        }
+    }
+    parser.sections
+}
@@ -0,0 +1,304 @@
+//! Screenshots of the ui without a GPU, for regression tests in CI. `HeadlessUiPlugin` lays the
+//! ui out for a fake window, and `render_ui` rasterizes the laid out nodes, their images and
+//! texts on the CPU, the way the ui render pipeline draws them. `assert_screenshot` compares the
+//! result with a golden PNG under `tests/screenshots`.
+
+use std::{fs, path::Path};
+
+use bevy::{
+    hierarchy::HierarchyPlugin,
+    input::InputPlugin,
+    prelude::*,
+    render::{render_resource::Shader, texture::ImagePlugin},
+    sprite::Rect,
+    text::{DefaultTextPipeline, TextPlugin},
+    transform::TransformPlugin,
+    ui::{CalculatedClip, UiPlugin},
+    window::{WindowId, WindowPlugin, WindowSettings},
+};
+use image::RgbaImage;
+use raw_window_handle::{RawWindowHandle, WebHandle};
+
+use crate::hover::node_rect;
+
+/// Channel difference up to which pixels count as equal, which absorbs antialiasing differences
+const CHANNEL_TOLERANCE: u8 = 8;
+
+/// The plugins laying out ui besides `MinimalPlugins` and `AssetPlugin`, and a primary window of
+/// `width` x `height` logical pixels which isn't backed by a window system
+pub struct HeadlessUiPlugin {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Plugin for HeadlessUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(WindowSettings {
+            add_primary_window: false,
+            exit_on_all_closed: false,
+            close_when_requested: false,
+        })
+        .add_plugin(WindowPlugin)
+        .add_plugin(InputPlugin)
+        .add_plugin(TransformPlugin)
+        .add_plugin(HierarchyPlugin)
+        .add_plugin(ImagePlugin)
+        // The ui and text plugins expect these from the render and sprite plugins
+        .add_asset::<Shader>()
+        .add_asset::<TextureAtlas>()
+        .add_plugin(TextPlugin)
+        .add_plugin(UiPlugin);
+
+        let window = Window::new(
+            WindowId::primary(),
+            &WindowDescriptor {
+                width: self.width,
+                height: self.height,
+                ..default()
+            },
+            self.width as u32,
+            self.height as u32,
+            1.0,
+            None,
+            RawWindowHandle::Web(WebHandle::empty()),
+        );
+        app.world.resource_mut::<Windows>().add(window);
+    }
+}
+
+/// Pixels of the primary window, y-down like the images
+struct Canvas {
+    image: RgbaImage,
+    scale: f32,
+}
+
+impl Canvas {
+    /// Blends `color`, times the `texture` area stretched over the rect, over the pixels of the
+    /// rect from `min` to `max` in y-up logical pixels, within `clip`
+    fn draw(
+        &mut self,
+        (min, max): (Vec2, Vec2),
+        clip: Option<&CalculatedClip>,
+        color: Color,
+        texture: Option<(&Image, Rect)>,
+    ) {
+        let (clip_min, clip_max) = match clip {
+            Some(clip) => (min.max(clip.clip.min), max.min(clip.clip.max)),
+            None => (min, max),
+        };
+        let height = self.image.height() as f32;
+        let x0 = (clip_min.x * self.scale).round().max(0.0) as u32;
+        let x1 = ((clip_max.x * self.scale).round().max(0.0) as u32).min(self.image.width());
+        let y0 = (height - clip_max.y * self.scale).round().max(0.0) as u32;
+        let y1 =
+            ((height - clip_min.y * self.scale).round().max(0.0) as u32).min(self.image.height());
+
+        let color = color.as_rgba_f32();
+        for y in y0..y1 {
+            for x in x0..x1 {
+                // Sample the texture at the pixel center, v runs from the top of the rect
+                let u = ((x as f32 + 0.5) / self.scale - min.x) / (max.x - min.x);
+                let v = (max.y - (height - y as f32 - 0.5) / self.scale) / (max.y - min.y);
+                let texel = texture.map_or([1.0; 4], |(image, area)| sample(image, area, u, v));
+                let source = [
+                    color[0] * texel[0],
+                    color[1] * texel[1],
+                    color[2] * texel[2],
+                    color[3] * texel[3],
+                ];
+                blend(self.image.get_pixel_mut(x, y), source);
+            }
+        }
+    }
+}
+
+/// Nearest texel at `u`, `v` within `area` of `image`, as rgba floats. Images which aren't 8 bit
+/// rgba are drawn white.
+fn sample(image: &Image, area: Rect, u: f32, v: f32) -> [f32; 4] {
+    let size = image.size();
+    let (width, height) = (size.x as usize, size.y as usize);
+    if width == 0 || height == 0 || image.data.len() != width * height * 4 {
+        return [1.0; 4];
+    }
+    let x = (area.min.x + u.clamp(0.0, 1.0) * (area.max.x - area.min.x)) as usize;
+    let y = (area.min.y + v.clamp(0.0, 1.0) * (area.max.y - area.min.y)) as usize;
+    let i = (y.min(height - 1) * width + x.min(width - 1)) * 4;
+    let texel = &image.data[i..i + 4];
+    [0, 1, 2, 3].map(|channel| texel[channel] as f32 / 255.0)
+}
+
+/// Alpha blends `source` over `pixel`
+fn blend(pixel: &mut image::Rgba<u8>, source: [f32; 4]) {
+    let alpha = source[3].clamp(0.0, 1.0);
+    for (destination, source) in pixel.0.iter_mut().zip(source).take(3) {
+        let blended = source * alpha + *destination as f32 / 255.0 * (1.0 - alpha);
+        *destination = (blended.clamp(0.0, 1.0) * 255.0).round() as u8;
+    }
+    pixel.0[3] = 255;
+}
+
+/// Renders the ui of the primary window. Nodes are drawn after their parents and root nodes in
+/// the z order of the ui layout, like the ui render pipeline does.
+pub fn render_ui(world: &mut World) -> RgbaImage {
+    let (width, height, scale) = match world.resource::<Windows>().get_primary() {
+        Some(window) => (
+            window.physical_width(),
+            window.physical_height(),
+            window.scale_factor() as f32,
+        ),
+        None => return RgbaImage::new(0, 0),
+    };
+    let clear_color = world
+        .get_resource::<ClearColor>()
+        .map_or_else(|| ClearColor::default().0, |clear_color| clear_color.0);
+    let mut canvas = Canvas {
+        image: RgbaImage::new(width, height),
+        scale,
+    };
+    canvas.draw(
+        (Vec2::ZERO, Vec2::new(width as f32, height as f32) / scale),
+        None,
+        clear_color,
+        None,
+    );
+
+    let mut roots: Vec<(Entity, f32)> = world
+        .query_filtered::<(Entity, &GlobalTransform), (With<Node>, Without<Parent>)>()
+        .iter(world)
+        .map(|(e, transform)| (e, transform.translation().z))
+        .collect();
+    roots.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+    for (root, _) in roots {
+        draw_node(world, root, &mut canvas);
+    }
+    canvas.image
+}
+
+fn draw_node(world: &World, entity: Entity, canvas: &mut Canvas) {
+    if world
+        .get::<Visibility>(entity)
+        .is_some_and(|visibility| !visibility.is_visible)
+        || world
+            .get::<Style>(entity)
+            .is_some_and(|style| style.display == Display::None)
+    {
+        return;
+    }
+    let (node, transform) = match (
+        world.get::<Node>(entity),
+        world.get::<GlobalTransform>(entity),
+    ) {
+        (Some(node), Some(transform)) => (node, transform),
+        _ => return,
+    };
+    let clip = world.get::<CalculatedClip>(entity);
+    let images = world.resource::<Assets<Image>>();
+
+    if let Some(color) = world.get::<UiColor>(entity) {
+        let texture = world
+            .get::<UiImage>(entity)
+            .and_then(|image| images.get(&image.0))
+            .map(|image| {
+                let area = Rect {
+                    min: Vec2::ZERO,
+                    max: image.size(),
+                };
+                (image, area)
+            });
+        canvas.draw(node_rect(node, transform, None), clip, color.0, texture);
+    }
+
+    if let Some(text) = world.get::<Text>(entity) {
+        let text_pipeline = world.resource::<DefaultTextPipeline>();
+        let texture_atlases = world.resource::<Assets<TextureAtlas>>();
+        let glyphs = text_pipeline
+            .get_glyphs(&entity)
+            .map_or(&[][..], |layout| &layout.glyphs[..]);
+        // Glyph positions are physical pixels from the bottom left of the node
+        let origin = transform.translation().truncate() - node.size / 2.0;
+        for glyph in glyphs {
+            let atlas = match texture_atlases.get(&glyph.atlas_info.texture_atlas) {
+                Some(atlas) => atlas,
+                None => continue,
+            };
+            let image = match images.get(&atlas.texture) {
+                Some(image) => image,
+                None => continue,
+            };
+            let area = atlas.textures[glyph.atlas_info.glyph_index];
+            let center = origin + glyph.position / canvas.scale;
+            let extents = (area.max - area.min) / canvas.scale / 2.0;
+            let color = text.sections[glyph.section_index].style.color;
+            canvas.draw(
+                (center - extents, center + extents),
+                clip,
+                color,
+                Some((image, area)),
+            );
+        }
+    }
+
+    if let Some(children) = world.get::<Children>(entity) {
+        for child in children.iter() {
+            draw_node(world, *child, canvas);
+        }
+    }
+}
+
+/// Compares `actual` with the golden `tests/screenshots/<name>.png`. At most `max_different`, a
+/// fraction, of the pixels may differ by more than a small tolerance. Run the tests with
+/// `UPDATE_SCREENSHOTS=1` to write the goldens instead, and review them before committing.
+pub fn assert_screenshot(name: &str, actual: &RgbaImage, max_different: f32) {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/screenshots");
+    let path = directory.join(format!("{}.png", name));
+    if std::env::var_os("UPDATE_SCREENSHOTS").is_some() {
+        fs::create_dir_all(&directory).unwrap();
+        actual.save(&path).unwrap();
+        return;
+    }
+
+    let actual_path = directory.join(format!("{}.actual.png", name));
+    if !path.exists() {
+        fs::create_dir_all(&directory).unwrap();
+        actual.save(&actual_path).unwrap();
+        panic!(
+            "Screenshot {} has no golden, see {:?} and record it with UPDATE_SCREENSHOTS=1",
+            name, actual_path
+        );
+    }
+    let expected = image::open(&path)
+        .unwrap_or_else(|err| panic!("Can't read golden {:?}: {}", path, err))
+        .into_rgba8();
+    if expected.dimensions() != actual.dimensions() {
+        actual.save(&actual_path).unwrap();
+        panic!(
+            "Screenshot {} is {:?} instead of {:?}, see {:?}",
+            name,
+            actual.dimensions(),
+            expected.dimensions(),
+            actual_path
+        );
+    }
+    let different = expected
+        .pixels()
+        .zip(actual.pixels())
+        .filter(|(expected, actual)| {
+            expected
+                .0
+                .iter()
+                .zip(actual.0.iter())
+                .any(|(expected, actual)| expected.abs_diff(*actual) > CHANNEL_TOLERANCE)
+        })
+        .count();
+    let fraction = different as f32 / (expected.width() * expected.height()).max(1) as f32;
+    if fraction > max_different {
+        actual.save(&actual_path).unwrap();
+        panic!(
+            "Screenshot {} differs in {} pixels ({:.2}%), see {:?}",
+            name,
+            different,
+            fraction * 100.0,
+            actual_path
+        );
+    }
+}
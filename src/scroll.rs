@@ -0,0 +1,211 @@
+//! Scrollable content slots. Content which doesn't fit its slot can be scrolled with the mouse
+//! wheel or by dragging it, and the scroll position survives re-renders of the slot.
+
+use bevy::{
+    ecs::{entity::Entities, system::SystemParam},
+    input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel},
+    prelude::*,
+    ui::{CalculatedClip, UiSystem},
+    utils::HashMap,
+};
+
+use crate::hover::{cursor_position, node_contains, ui_tree_root};
+
+pub struct ScrollPlugin;
+
+impl Plugin for ScrollPlugin {
+    fn build(&self, app: &mut App) {
+        // Drags are known before `Update`, so click handlers can ignore them like panel grabs
+        app.init_resource::<ScrollPositions>()
+            .add_system_to_stage(CoreStage::PreUpdate, mouse_scroll.after(UiSystem::Focus))
+            .add_system(apply_scroll)
+            .add_system(forget_despawned_trees);
+    }
+}
+
+/// Marks a ui node whose children can be scrolled. The node should use `Overflow::Hidden` so
+/// scrolled out content is clipped.
+#[derive(Component)]
+pub struct Scrollable {
+    /// Name of the branch shown in the node. The position is remembered per ui tree and branch.
+    pub branch: &'static str,
+}
+
+/// Identifies a branch across re-renders by the root of its ui tree and the branch name
+type ScrollId = (Entity, &'static str);
+
+/// Distance the mouse has to move with grabbed content before it's dragged rather than clicked
+const DRAG_THRESHOLD: f32 = 4.0;
+
+/// Content pressed with the left button, which is dragged once the mouse moved far enough
+struct Grab {
+    id: ScrollId,
+    travelled: f32,
+    dragging: bool,
+}
+
+#[derive(Default)]
+pub struct ScrollPositions {
+    positions: HashMap<ScrollId, f32>,
+    grab: Option<Grab>,
+    /// Whether grabbed content was let go this frame without dragging it
+    clicked: bool,
+}
+
+impl ScrollPositions {
+    /// Whether the left button is held down on content which may still turn into a drag
+    pub fn is_grabbed(&self) -> bool {
+        self.grab.is_some()
+    }
+
+    /// Whether the left button is held down after dragging content to scroll it
+    pub fn is_dragging(&self) -> bool {
+        self.grab.as_ref().is_some_and(|grab| grab.dragging)
+    }
+
+    /// Whether grabbed content was let go this frame without dragging it, which makes it a click
+    pub fn was_clicked(&self) -> bool {
+        self.clicked
+    }
+}
+
+#[derive(SystemParam)]
+struct ScrollInput<'w, 's> {
+    mouse_wheel_events: EventReader<'w, 's, MouseWheel>,
+    mouse_motion_events: EventReader<'w, 's, MouseMotion>,
+    mouse_button_input: Res<'w, Input<MouseButton>>,
+    windows: Res<'w, Windows>,
+    buttons: Query<'w, 's, &'static Interaction, With<Button>>,
+}
+
+type ScrollableQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Scrollable,
+        &'static Node,
+        &'static GlobalTransform,
+        Option<&'static CalculatedClip>,
+        &'static Children,
+    ),
+>;
+
+fn mouse_scroll(
+    mut input: ScrollInput,
+    mut scroll: ResMut<ScrollPositions>,
+    query: ScrollableQuery,
+    nodes: Query<&Node>,
+    parents: Query<&Parent>,
+) {
+    let mouse_button_input = &input.mouse_button_input;
+    let wheel: f32 = input
+        .mouse_wheel_events
+        .iter()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y * 20.0,
+            MouseScrollUnit::Pixel => event.y,
+        })
+        .sum();
+    let motion: Vec2 = input
+        .mouse_motion_events
+        .iter()
+        .map(|event| &event.delta)
+        .sum();
+
+    scroll.clicked = false;
+    if !mouse_button_input.pressed(MouseButton::Left) {
+        if let Some(grab) = scroll.grab.take() {
+            scroll.clicked = !grab.dragging;
+        }
+    }
+    if let Some(grab) = &mut scroll.grab {
+        grab.travelled += motion.length();
+        grab.dragging |= grab.travelled > DRAG_THRESHOLD;
+    }
+    // Buttons inside scrollable content handle their own clicks
+    let button_pressed = input
+        .buttons
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+
+    let cursor = cursor_position(&input.windows);
+
+    for (e, scrollable, node, transform, clip, children) in query.iter() {
+        let id = (ui_tree_root(e, &parents), scrollable.branch);
+        let hovered = cursor.is_some_and(|cursor| node_contains(node, transform, clip, cursor));
+        let content_height: f32 = children
+            .iter()
+            .filter_map(|child| nodes.get(*child).ok())
+            .map(|child| child.size.y)
+            .sum();
+        let max_scroll = (content_height - node.size.y).max(0.0);
+        // Remember the grabbed branch rather than the entity, a re-render replaces it. Content
+        // which fits can't be dragged, so pressing on it is a click right away.
+        if hovered
+            && max_scroll > 0.0
+            && !button_pressed
+            && mouse_button_input.just_pressed(MouseButton::Left)
+        {
+            scroll.grab = Some(Grab {
+                id,
+                travelled: 0.0,
+                dragging: false,
+            });
+        }
+
+        let mut dy = 0.0;
+        if hovered {
+            dy += wheel;
+        }
+        if scroll
+            .grab
+            .as_ref()
+            .is_some_and(|grab| grab.id == id && grab.dragging)
+        {
+            dy += motion.y;
+        }
+        // Layout may not have caught up with a re-render yet, so only clamp on actual input
+        if dy == 0.0 {
+            continue;
+        }
+
+        let position = scroll.positions.entry(id).or_default();
+        *position = (*position + dy).clamp(-max_scroll, 0.0);
+    }
+}
+
+/// Offsets the children of every scrollable, including freshly re-rendered ones
+fn apply_scroll(
+    scroll: Res<ScrollPositions>,
+    query: Query<(Entity, &Scrollable, &Children)>,
+    mut styles: Query<&mut Style>,
+    parents: Query<&Parent>,
+) {
+    for (e, scrollable, children) in query.iter() {
+        let position = scroll
+            .positions
+            .get(&(ui_tree_root(e, &parents), scrollable.branch))
+            .copied()
+            .unwrap_or_default();
+        for child in children.iter() {
+            if let Ok(mut style) = styles.get_mut(*child) {
+                if style.position.top != Val::Px(position) {
+                    style.position.top = Val::Px(position);
+                }
+            }
+        }
+    }
+}
+
+fn forget_despawned_trees(mut scroll: ResMut<ScrollPositions>, entities: &Entities) {
+    if scroll
+        .positions
+        .keys()
+        .any(|(root, _)| !entities.contains(*root))
+    {
+        scroll
+            .positions
+            .retain(|(root, _), _| entities.contains(*root));
+    }
+}
@@ -0,0 +1,196 @@
+//! Textual snapshots of ui trees, so changes to spawn functions show up as reviewable diffs.
+//!
+//! Every entity is a `- ` line listing its components by name, followed by its style fields
+//! which differ from the default, color, texts and image, and then its children. Localized texts,
+//! facts and galleries are filled in from assets, so their keys, index and image list are listed
+//! as well and the snapshot doesn't depend on what has finished loading:
+//!
+//! ```text
+//! - ComputedVisibility, FocusPolicy, GlobalTransform, Node, Style, Text, Transform, Visibility
+//!   style.margin: UiRect { left: Px(5.0), right: Px(5.0), top: Px(5.0), bottom: Px(5.0) }
+//!   text: "" font.ttf 20.0 Rgba { red: 0.0, green: 0.0, blue: 1.0, alpha: 1.0 }
+//!   localized text: "infobox-cats"
+//! ```
+//!
+//! `assert_snapshot` compares a snapshot with the one checked in under `tests/snapshots`.
+
+use std::{fmt::Write, fs, path::Path};
+
+use bevy::{asset::HandleId, prelude::*};
+
+use crate::{
+    facts::FactText,
+    gallery::Gallery,
+    locale::{LocalizedLabel, LocalizedText},
+};
+
+/// Hierarchy components, which show up as the structure of the snapshot instead
+const SKIPPED_COMPONENTS: [&str; 2] = ["Parent", "Children"];
+
+/// Dumps the tree below `root`, including `root` itself
+pub fn snapshot(world: &World, root: Entity) -> String {
+    let mut out = String::new();
+    write_entity(world, root, 0, &mut out);
+    out
+}
+
+fn write_entity(world: &World, entity: Entity, depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth);
+    let _ = writeln!(
+        out,
+        "{}- {}",
+        indent,
+        component_names(world, entity).join(", ")
+    );
+
+    let indent = "  ".repeat(depth + 1);
+    if let Some(style) = world.get::<Style>(entity) {
+        write_style(style, &indent, out);
+    }
+    if let Some(color) = world.get::<UiColor>(entity) {
+        let _ = writeln!(out, "{}color: {:?}", indent, color.0);
+    }
+    if let Some(text) = world.get::<Text>(entity) {
+        for section in &text.sections {
+            let _ = writeln!(
+                out,
+                "{}text: {:?} {} {:?} {:?}",
+                indent,
+                section.value,
+                asset_path(world, section.style.font.id),
+                section.style.font_size,
+                section.style.color
+            );
+        }
+    }
+    if let Some(localized) = world.get::<LocalizedText>(entity) {
+        let _ = writeln!(out, "{}localized text: {:?}", indent, localized.0);
+    }
+    if let Some(localized) = world.get::<LocalizedLabel>(entity) {
+        let _ = writeln!(out, "{}localized label: {:?}", indent, localized.0);
+    }
+    if let Some(fact) = world.get::<FactText>(entity) {
+        let _ = writeln!(out, "{}fact: {}", indent, fact.index);
+    }
+    if let Some(gallery) = world.get::<Gallery>(entity) {
+        let _ = writeln!(out, "{}gallery: {:?}", indent, gallery.images());
+    }
+    if let Some(image) = world.get::<UiImage>(entity) {
+        // Nodes without an image show the default white one, which has no path
+        if image.0 != UiImage::default().0 {
+            let _ = writeln!(out, "{}image: {}", indent, asset_path(world, image.0.id));
+        }
+    }
+    if let Some(children) = world.get::<Children>(entity) {
+        for child in children.iter() {
+            write_entity(world, *child, depth + 1, out);
+        }
+    }
+}
+
+/// Short names of the components of `entity`, sorted
+fn component_names(world: &World, entity: Entity) -> Vec<&str> {
+    let location = match world.entities().get(entity) {
+        Some(location) => location,
+        None => return vec!["<despawned>"],
+    };
+    let mut names: Vec<&str> = world.archetypes()[location.archetype_id]
+        .components()
+        .filter_map(|id| world.components().get_info(id))
+        .map(|info| {
+            let name = info.name();
+            name.rsplit("::").next().unwrap_or(name)
+        })
+        .filter(|name| !SKIPPED_COMPONENTS.contains(name))
+        .collect();
+    names.sort_unstable();
+    names
+}
+
+/// Style fields which differ from the default
+fn write_style(style: &Style, indent: &str, out: &mut String) {
+    let default = Style::default();
+    macro_rules! fields {
+        ($($field:ident),*) => {
+            $(
+                if style.$field != default.$field {
+                    let _ = writeln!(
+                        out,
+                        "{}style.{}: {:?}",
+                        indent,
+                        stringify!($field),
+                        style.$field
+                    );
+                }
+            )*
+        };
+    }
+    fields!(
+        display,
+        position_type,
+        direction,
+        flex_direction,
+        flex_wrap,
+        align_items,
+        align_self,
+        align_content,
+        justify_content,
+        position,
+        margin,
+        padding,
+        border,
+        flex_grow,
+        flex_shrink,
+        flex_basis,
+        size,
+        min_size,
+        max_size,
+        aspect_ratio,
+        overflow
+    );
+}
+
+fn asset_path(world: &World, id: HandleId) -> String {
+    world
+        .get_resource::<AssetServer>()
+        .and_then(|asset_server| asset_server.get_handle_path(id))
+        .map_or_else(
+            || "<no path>".to_owned(),
+            |path| path.path().display().to_string(),
+        )
+}
+
+/// Compares `actual` with the snapshot `tests/snapshots/<name>.snap`. Run the tests with
+/// `UPDATE_SNAPSHOTS=1` to write the snapshots instead, and review the diff.
+pub fn assert_snapshot(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{}.snap", name));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|err| {
+        panic!(
+            "Can't read snapshot {:?}: {}, run with UPDATE_SNAPSHOTS=1 to write it",
+            path, err
+        )
+    });
+    if expected != actual {
+        let line = expected
+            .lines()
+            .zip(actual.lines())
+            .position(|(expected, actual)| expected != actual)
+            .unwrap_or_else(|| expected.lines().count().min(actual.lines().count()));
+        panic!(
+            "Snapshot {} differs from line {}, run with UPDATE_SNAPSHOTS=1 to accept it\n\
+             --- expected\n{}\n--- actual\n{}",
+            name,
+            line + 1,
+            expected,
+            actual
+        );
+    }
+}
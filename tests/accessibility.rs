@@ -0,0 +1,204 @@
+use std::{thread, time::Duration};
+
+use bevy::{
+    asset::{AssetPlugin, LoadState},
+    prelude::*,
+};
+use bevy_ui_pattern_example::{
+    accessibility::{
+        AccessibilityNode, AccessibilityPlugin, AccessibilityTree, AccessibleLabel, AccessibleRole,
+    },
+    branch::{run_ui_schedule, UiSchedule},
+    gallery::GalleryPlugin,
+    infobox::{
+        FrameCounter, UiInfoboxAnimalsState, UiInfoboxCatsContentState, UiInfoboxPanel,
+        UiInfoboxRoot, UiInfoboxState,
+    },
+    input_device::InputDevicePlugin,
+    locale::LocalePlugin,
+    panel::PanelGeometry,
+    screenshot::HeadlessUiPlugin,
+};
+
+#[derive(Component)]
+struct Root;
+
+fn text(value: &str) -> Text {
+    Text::from_section(value, TextStyle::default())
+}
+
+fn node(role: AccessibleRole, label: &str, children: Vec<AccessibilityNode>) -> AccessibilityNode {
+    AccessibilityNode {
+        role,
+        label: label.to_owned(),
+        children,
+    }
+}
+
+/// Accessibility trees under an infobox in `state`, once its translations are loaded. The root
+/// has no role, so its children are listed in its place.
+fn infobox(state: UiInfoboxState) -> Vec<AccessibilityNode> {
+    let mut ui_schedule = UiSchedule::default();
+    ui_schedule
+        .add_branch::<UiInfoboxPanel>("Ui-level-1")
+        .add_branch::<UiInfoboxAnimalsState>("Ui-level-2")
+        .add_branch::<UiInfoboxCatsContentState>("Ui-level-3");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_plugin(HeadlessUiPlugin {
+            width: 800.0,
+            height: 600.0,
+        })
+        .add_plugin(LocalePlugin)
+        .add_plugin(InputDevicePlugin)
+        .add_plugin(GalleryPlugin)
+        .add_plugin(AccessibilityPlugin::<UiInfoboxRoot>::default())
+        .insert_resource(FrameCounter(0))
+        .insert_resource(ui_schedule)
+        .add_system(run_ui_schedule.exclusive_system());
+    let root = app
+        .world
+        .spawn()
+        .insert(UiInfoboxRoot)
+        .insert(PanelGeometry::new(
+            Vec2::new(20.0, 10.0),
+            Vec2::new(600.0, 400.0),
+        ))
+        .insert(state)
+        .id();
+
+    // Translations load in the background, the labels follow in the next updates
+    for _ in 0..500 {
+        thread::sleep(Duration::from_millis(10));
+        app.update();
+        let loaded = app
+            .world
+            .resource::<AssetServer>()
+            .get_load_state("locales/en-US/infobox.ftl")
+            == LoadState::Loaded;
+        if loaded {
+            app.update();
+            app.update();
+            let tree = app.world.resource::<AccessibilityTree>();
+            assert!(tree.roots.iter().all(|(e, _)| *e == root));
+            return tree.roots.iter().map(|(_, node)| node.clone()).collect();
+        }
+    }
+    panic!("translations didn't load");
+}
+
+#[test]
+fn snapshot_skips_nodes_without_role() {
+    let mut app = App::new();
+    app.add_plugin(AccessibilityPlugin::<Root>::default());
+
+    let root = app
+        .world
+        .spawn()
+        .insert(Root)
+        .insert(AccessibleRole::Panel)
+        .with_children(|parent| {
+            parent
+                .spawn()
+                .insert(text("Animals"))
+                .insert(AccessibleRole::Heading);
+            // Layout only node, its children take its place
+            parent.spawn().with_children(|parent| {
+                parent
+                    .spawn()
+                    .insert(AccessibleRole::Tab)
+                    .with_children(|parent| {
+                        parent.spawn().insert(text("Dog stuff"));
+                        parent
+                            .spawn()
+                            .insert(AccessibleRole::Image)
+                            .insert(AccessibleLabel("A dog".to_owned()));
+                    });
+            });
+            parent
+                .spawn()
+                .insert(AccessibleRole::Text)
+                .insert(text("Hidden"))
+                .insert(Visibility { is_visible: false });
+        })
+        .id();
+    app.update();
+
+    let tree = app.world.resource::<AccessibilityTree>();
+    assert_eq!(
+        tree.roots,
+        vec![(
+            root,
+            node(
+                AccessibleRole::Panel,
+                "",
+                vec![
+                    node(AccessibleRole::Heading, "Animals", vec![]),
+                    node(
+                        AccessibleRole::Tab,
+                        "Dog stuff",
+                        vec![node(AccessibleRole::Image, "A dog", vec![])]
+                    ),
+                ]
+            )
+        )]
+    );
+}
+
+#[test]
+fn infobox_cats_kittens_tree() {
+    assert_eq!(
+        infobox(UiInfoboxState::default()),
+        vec![
+            node(AccessibleRole::Heading, "Animals", vec![]),
+            node(
+                AccessibleRole::Tab,
+                "Cat stuff",
+                vec![node(
+                    AccessibleRole::Tab,
+                    "Kittens",
+                    vec![node(
+                        AccessibleRole::Image,
+                        "A fluffy ginger kitten stretching both front paws up in the air",
+                        vec![]
+                    )]
+                )]
+            ),
+            node(
+                AccessibleRole::Text,
+                "Left click dogs, right click cats, middle click hide",
+                vec![]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn infobox_dogs_tree() {
+    let state = UiInfoboxState {
+        animal_state: UiInfoboxAnimalsState::Dogs,
+        ..default()
+    };
+    assert_eq!(
+        infobox(state),
+        vec![
+            node(AccessibleRole::Heading, "Animals", vec![]),
+            node(
+                AccessibleRole::Tab,
+                "Dog stuff",
+                vec![node(
+                    AccessibleRole::Image,
+                    "A white, black and tan Jack Russell terrier sitting with its mouth open",
+                    vec![]
+                )]
+            ),
+            node(
+                AccessibleRole::Text,
+                "Left click dogs, right click cats, middle click hide",
+                vec![]
+            ),
+        ]
+    );
+}
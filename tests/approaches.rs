@@ -0,0 +1,339 @@
+//! Runs the infobox under each approach of `branch` headlessly and checks that random click
+//! sequences, fed to the click systems of the examples, leave identical trees behind
+
+use bevy::{
+    asset::AssetPlugin,
+    input::{mouse::MouseButtonInput, ButtonState},
+    math::DVec2,
+    prelude::*,
+};
+use bevy_ui_pattern_example::{
+    branch::{spawn_added_branches, AddUiSchedule, UiSchedule, UiScheduleMetrics, UiSlotRegistry},
+    focus::FocusPlugin,
+    infobox::{
+        mouse_click_system, registry_mouse_click_system, FrameCounter, UiInfoboxAnimals,
+        UiInfoboxAnimalsState, UiInfoboxCat, UiInfoboxCatsContentState, UiInfoboxPanel,
+        UiInfoboxRoot, UiInfoboxState,
+    },
+    locale::LocalizedText,
+    panel::{PanelGeometry, PanelPlugin},
+    screenshot::HeadlessUiPlugin,
+    scroll::ScrollPlugin,
+};
+use proptest::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+enum Approach {
+    Components,
+    ComponentsSchedule,
+    Resources,
+}
+
+/// The parts of a ui node the approaches have to agree on
+#[derive(Debug, PartialEq)]
+struct UiTree {
+    kind: &'static str,
+    visible: bool,
+    display: Option<Display>,
+    color: Option<Color>,
+    image: Option<Handle<Image>>,
+    /// Value, font, size and color of every text section
+    texts: Vec<(String, Handle<Font>, f32, Color)>,
+    localized: Option<&'static str>,
+    children: Vec<UiTree>,
+}
+
+impl UiTree {
+    fn new(world: &World, entity: Entity) -> Self {
+        let kind = if world.get::<Text>(entity).is_some() {
+            "text"
+        } else if world.get::<Button>(entity).is_some() {
+            "button"
+        } else if world.get::<Node>(entity).is_some() {
+            "node"
+        } else {
+            "entity"
+        };
+        Self {
+            kind,
+            visible: world
+                .get::<Visibility>(entity)
+                .is_none_or(|visibility| visibility.is_visible),
+            display: world.get::<Style>(entity).map(|style| style.display),
+            color: world.get::<UiColor>(entity).map(|color| color.0),
+            image: world.get::<UiImage>(entity).map(|image| image.0.clone()),
+            texts: world.get::<Text>(entity).map_or_else(Vec::new, |text| {
+                text.sections
+                    .iter()
+                    .map(|section| {
+                        (
+                            section.value.clone(),
+                            section.style.font.clone(),
+                            section.style.font_size,
+                            section.style.color,
+                        )
+                    })
+                    .collect()
+            }),
+            localized: world.get::<LocalizedText>(entity).map(|text| text.0),
+            children: world
+                .get::<Children>(entity)
+                .map_or_else(Vec::new, |children| {
+                    children
+                        .iter()
+                        .map(|child| Self::new(world, *child))
+                        .collect()
+                }),
+        }
+    }
+}
+
+/// Number of infoboxes, side by side
+const INFOBOXES: usize = 2;
+
+fn geometry(infobox: usize) -> PanelGeometry {
+    PanelGeometry::new(
+        Vec2::new(400.0 * infobox as f32, 100.0),
+        Vec2::new(350.0, 400.0),
+    )
+}
+
+fn app(approach: Approach) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_plugin(HeadlessUiPlugin {
+            width: 400.0 * INFOBOXES as f32,
+            height: 600.0,
+        })
+        .add_plugin(FocusPlugin)
+        .add_plugin(PanelPlugin)
+        .add_plugin(ScrollPlugin)
+        .insert_resource(FrameCounter(0));
+
+    match approach {
+        Approach::Components => {
+            app.add_system(mouse_click_system)
+                .add_system(spawn_added_branches::<UiInfoboxPanel>.after(mouse_click_system))
+                .add_system(
+                    spawn_added_branches::<UiInfoboxAnimalsState>
+                        .after(spawn_added_branches::<UiInfoboxPanel>),
+                )
+                .add_system(
+                    spawn_added_branches::<UiInfoboxCatsContentState>
+                        .after(spawn_added_branches::<UiInfoboxAnimalsState>),
+                );
+        }
+        Approach::ComponentsSchedule => {
+            let mut ui_schedule = UiSchedule::default();
+            ui_schedule
+                .add_branch::<UiInfoboxPanel>("Ui-level-1")
+                .add_branch::<UiInfoboxAnimalsState>("Ui-level-2")
+                .add_branch::<UiInfoboxCatsContentState>("Ui-level-3")
+                .until_settled(4);
+            app.add_ui_schedule(ui_schedule)
+                .add_system(mouse_click_system);
+        }
+        Approach::Resources => {
+            let mut ui_schedule = UiSchedule::default();
+            ui_schedule
+                .add_registered_branch::<UiInfoboxPanel>("Ui-level-1")
+                .add_registered_branch::<UiInfoboxAnimalsState>("Ui-level-2")
+                .add_registered_branch::<UiInfoboxCatsContentState>("Ui-level-3");
+            app.init_resource::<UiSlotRegistry<UiInfoboxState>>()
+                .add_ui_schedule(ui_schedule)
+                .add_system(registry_mouse_click_system);
+        }
+    }
+
+    for infobox in 0..INFOBOXES {
+        let mut root = app.world.spawn();
+        root.insert(UiInfoboxRoot).insert(geometry(infobox));
+        if matches!(approach, Approach::Resources) {
+            let root = root.id();
+            app.world
+                .resource_mut::<UiSlotRegistry<UiInfoboxState>>()
+                .insert_tree(root, UiInfoboxState::default());
+        } else {
+            root.insert(UiInfoboxState::default());
+        }
+    }
+    app
+}
+
+fn snapshot(app: &mut App) -> Vec<UiTree> {
+    let mut roots = app.world.query_filtered::<Entity, With<UiInfoboxRoot>>();
+    let roots: Vec<Entity> = roots.iter(&app.world).collect();
+    roots
+        .into_iter()
+        .map(|root| UiTree::new(&app.world, root))
+        .collect()
+}
+
+/// Updates until an update leaves the tree as it was
+fn settle(app: &mut App) -> Vec<UiTree> {
+    let mut tree = snapshot(app);
+    for _ in 0..16 {
+        app.update();
+        let next = snapshot(app);
+        if next == tree {
+            return tree;
+        }
+        tree = next;
+    }
+    panic!("ui didn't settle");
+}
+
+/// Tree of every approach after `clicks`, each followed by updates until the ui settles
+fn run(approach: Approach, clicks: &[(MouseButton, usize)]) -> Vec<UiTree> {
+    let mut app = app(approach);
+    let mut tree = settle(&mut app);
+    for (button, infobox) in clicks {
+        click(&mut app, *button, *infobox);
+        tree = settle(&mut app);
+    }
+    tree
+}
+
+/// Clicks `button` over the center of `infobox` for one update
+fn click(app: &mut App, button: MouseButton, infobox: usize) {
+    let geometry = geometry(infobox);
+    let center = geometry.position + geometry.size / 2.0;
+    let mut windows = app.world.resource_mut::<Windows>();
+    let window = windows.get_primary_mut().unwrap();
+    // Cursor positions are y-up
+    let cursor = DVec2::new(center.x as f64, (window.height() - center.y) as f64);
+    window.update_cursor_physical_position_from_backend(Some(cursor * window.scale_factor()));
+
+    for state in [ButtonState::Pressed, ButtonState::Released] {
+        app.world.send_event(MouseButtonInput { button, state });
+        app.update();
+    }
+}
+
+#[test]
+fn ui_schedule_skips_unchanged_frames() {
+    let mut app = app(Approach::ComponentsSchedule);
+    settle(&mut app);
+    let run_frames = app.world.resource::<UiScheduleMetrics>().run_frames;
+    let skipped_frames = app.world.resource::<UiScheduleMetrics>().skipped_frames;
+
+    for _ in 0..10 {
+        app.update();
+    }
+    let metrics = app.world.resource::<UiScheduleMetrics>();
+    assert_eq!(metrics.run_frames, run_frames);
+    assert_eq!(metrics.skipped_frames, skipped_frames + 10);
+
+    click(&mut app, MouseButton::Left, 0);
+    assert!(app.world.resource::<UiScheduleMetrics>().run_frames > run_frames);
+}
+
+#[test]
+fn resources_rebuild_only_the_changed_branch() {
+    let mut app = app(Approach::Resources);
+    let tree = settle(&mut app);
+    let mut animals = app
+        .world
+        .query_filtered::<&Children, With<UiInfoboxAnimals>>();
+    let contents: Vec<Entity> = animals
+        .iter(&app.world)
+        .flat_map(|children| children.iter().copied())
+        .collect();
+
+    // Right click cycles the cat content, the animals branch around it is kept
+    click(&mut app, MouseButton::Right, 0);
+    assert_ne!(settle(&mut app), tree);
+    let kept: Vec<Entity> = animals
+        .iter(&app.world)
+        .flat_map(|children| children.iter().copied())
+        .collect();
+    assert_eq!(kept, contents);
+}
+
+#[test]
+fn resources_infoboxes_have_their_own_state() {
+    let mut app = app(Approach::Resources);
+    let tree = settle(&mut app);
+    let first = app
+        .world
+        .query_filtered::<Entity, With<UiInfoboxRoot>>()
+        .iter(&app.world)
+        .next()
+        .unwrap();
+    app.world
+        .resource_mut::<UiSlotRegistry<UiInfoboxState>>()
+        .state_mut(first)
+        .unwrap()
+        .click(MouseButton::Left);
+
+    let changed = settle(&mut app);
+    let roots: Vec<Entity> = app
+        .world
+        .query_filtered::<Entity, With<UiInfoboxRoot>>()
+        .iter(&app.world)
+        .collect();
+    for (i, root) in roots.into_iter().enumerate() {
+        assert_eq!(changed[i] == tree[i], root != first);
+    }
+}
+
+#[test]
+fn resources_fill_new_slots_in_one_update() {
+    let mut app = app(Approach::Resources);
+    settle(&mut app);
+    fn show(app: &mut App, animal_state: UiInfoboxAnimalsState) {
+        let mut registry = app.world.resource_mut::<UiSlotRegistry<UiInfoboxState>>();
+        for (_, state) in registry.states_mut() {
+            state.animal_state = animal_state;
+        }
+    }
+    show(&mut app, UiInfoboxAnimalsState::Dogs);
+    settle(&mut app);
+
+    // The cats branch spawns the cat slot, which is filled in the same update
+    show(&mut app, UiInfoboxAnimalsState::Cats);
+    app.update();
+    let mut cats = app.world.query_filtered::<&Children, With<UiInfoboxCat>>();
+    assert_eq!(cats.iter(&app.world).count(), INFOBOXES);
+    assert!(cats.iter(&app.world).all(|children| !children.is_empty()));
+}
+
+// The registry only asserts in debug builds, release builds log the error
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "stale ui slot")]
+fn registry_asserts_on_slots_despawned_behind_its_back() {
+    let mut app = app(Approach::Resources);
+    settle(&mut app);
+    let slot = app
+        .world
+        .query_filtered::<Entity, With<UiInfoboxAnimals>>()
+        .iter(&app.world)
+        .next()
+        .unwrap();
+    app.world.entity_mut(slot).despawn_recursive();
+    app.update();
+}
+
+fn button() -> impl Strategy<Value = MouseButton> {
+    prop_oneof![
+        Just(MouseButton::Left),
+        Just(MouseButton::Middle),
+        Just(MouseButton::Right),
+    ]
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn approaches_build_identical_trees(
+        clicks in prop::collection::vec((button(), 0..INFOBOXES), 0..12)
+    ) {
+        let components = run(Approach::Components, &clicks);
+        prop_assert!(!components.is_empty());
+        prop_assert_eq!(&run(Approach::ComponentsSchedule, &clicks), &components);
+        prop_assert_eq!(&run(Approach::Resources, &clicks), &components);
+    }
+}
@@ -0,0 +1,134 @@
+//! Records clicks on infoboxes side by side and replays them into the shared click system of the
+//! resources approach, which only finds the clicked infobox through the recorded cursor
+
+use std::{env, fs, process};
+
+use bevy::{
+    asset::AssetPlugin,
+    input::{mouse::MouseButtonInput, ButtonState},
+    math::DVec2,
+    prelude::*,
+};
+use bevy_ui_pattern_example::{
+    branch::{AddUiSchedule, UiSchedule, UiSlotRegistry},
+    focus::FocusPlugin,
+    infobox::{
+        registry_mouse_click_system, FrameCounter, UiInfoboxAnimalsState,
+        UiInfoboxCatsContentState, UiInfoboxPanel, UiInfoboxRoot, UiInfoboxState,
+    },
+    panel::{PanelGeometry, PanelPlugin},
+    replay::{InputScript, InputScriptPlugin},
+    screenshot::HeadlessUiPlugin,
+    scroll::ScrollPlugin,
+};
+
+/// Number of infoboxes, side by side
+const INFOBOXES: usize = 2;
+
+fn geometry(infobox: usize) -> PanelGeometry {
+    PanelGeometry::new(
+        Vec2::new(400.0 * infobox as f32, 100.0),
+        Vec2::new(350.0, 400.0),
+    )
+}
+
+/// The resources example without its assets, and the roots of its infoboxes
+fn app(script: InputScript) -> (App, Vec<Entity>) {
+    let mut ui_schedule = UiSchedule::default();
+    ui_schedule
+        .add_registered_branch::<UiInfoboxPanel>("Ui-level-1")
+        .add_registered_branch::<UiInfoboxAnimalsState>("Ui-level-2")
+        .add_registered_branch::<UiInfoboxCatsContentState>("Ui-level-3");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_plugin(HeadlessUiPlugin {
+            width: 400.0 * INFOBOXES as f32,
+            height: 600.0,
+        })
+        .add_plugin(FocusPlugin)
+        .add_plugin(PanelPlugin)
+        .add_plugin(ScrollPlugin)
+        .add_plugin(InputScriptPlugin)
+        .insert_resource(script)
+        .insert_resource(FrameCounter(0))
+        .init_resource::<UiSlotRegistry<UiInfoboxState>>()
+        .add_ui_schedule(ui_schedule)
+        .add_system(registry_mouse_click_system);
+
+    let roots = (0..INFOBOXES)
+        .map(|infobox| {
+            let root = app
+                .world
+                .spawn()
+                .insert(UiInfoboxRoot)
+                .insert(geometry(infobox))
+                .id();
+            app.world
+                .resource_mut::<UiSlotRegistry<UiInfoboxState>>()
+                .insert_tree(root, UiInfoboxState::default());
+            root
+        })
+        .collect();
+    (app, roots)
+}
+
+fn states(app: &App, roots: &[Entity]) -> Vec<UiInfoboxState> {
+    let registry = app.world.resource::<UiSlotRegistry<UiInfoboxState>>();
+    roots
+        .iter()
+        .map(|root| registry.state(*root).unwrap().clone())
+        .collect()
+}
+
+/// Moves the cursor over the center of `infobox`
+fn hover(app: &mut App, infobox: usize) {
+    let geometry = geometry(infobox);
+    let center = geometry.position + geometry.size / 2.0;
+    let mut windows = app.world.resource_mut::<Windows>();
+    let window = windows.get_primary_mut().unwrap();
+    // Cursor positions are y-up
+    let cursor = DVec2::new(center.x as f64, (window.height() - center.y) as f64);
+    window.update_cursor_physical_position_from_backend(Some(cursor * window.scale_factor()));
+}
+
+#[test]
+fn replay_repeats_recording() {
+    let path = env::temp_dir().join(format!("ui_input_script_{}.ron", process::id()));
+
+    let (mut recording, roots) = app(InputScript::record(&path));
+    let mut recorded = Vec::new();
+    let buttons = [MouseButton::Right, MouseButton::Left, MouseButton::Middle];
+    for frame in 0..60 {
+        for button in buttons {
+            recording.world.send_event(MouseButtonInput {
+                button,
+                state: ButtonState::Released,
+            });
+        }
+        if frame % 7 == 3 {
+            hover(&mut recording, frame / 7 % INFOBOXES);
+            recording.world.send_event(MouseButtonInput {
+                button: buttons[frame / 7 % 3],
+                state: ButtonState::Pressed,
+            });
+        }
+        recording.update();
+        recorded.push(states(&recording, &roots));
+    }
+
+    let (mut replay, roots) = app(InputScript::load(&path).unwrap());
+    let mut replayed = Vec::new();
+    for _ in 0..60 {
+        replay.update();
+        replayed.push(states(&replay, &roots));
+    }
+    fs::remove_file(&path).unwrap();
+
+    assert!(replay.world.resource::<InputScript>().is_finished());
+    // Both infoboxes were clicked on their own, so the cursor has to be replayed as well
+    let last = recorded.last().unwrap();
+    assert_ne!(last[0], last[1]);
+    assert_eq!(replayed, recorded);
+}
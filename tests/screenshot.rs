@@ -0,0 +1,108 @@
+//! Screenshots of the infobox in each of its states, see `tests/screenshots`
+
+use std::{thread, time::Duration};
+
+use bevy::{
+    asset::{AssetPlugin, LoadState},
+    prelude::*,
+};
+use bevy_ui_pattern_example::{
+    branch::{run_ui_schedule, UiSchedule},
+    facts::FactsPlugin,
+    gallery::GalleryPlugin,
+    infobox::{
+        FrameCounter, UiInfoboxAnimalsState, UiInfoboxCatsContentState, UiInfoboxPanel,
+        UiInfoboxRoot, UiInfoboxState,
+    },
+    input_device::InputDevicePlugin,
+    locale::LocalePlugin,
+    panel::PanelGeometry,
+    screenshot::{assert_screenshot, render_ui, HeadlessUiPlugin},
+};
+use image::RgbaImage;
+
+/// Fraction of the pixels which may differ, e.g. by font rasterization
+const MAX_DIFFERENT: f32 = 0.005;
+
+/// Renders an infobox in `state`, once its fonts, translations and images are loaded
+fn screenshot(state: UiInfoboxState) -> RgbaImage {
+    let mut ui_schedule = UiSchedule::default();
+    ui_schedule
+        .add_branch::<UiInfoboxPanel>("Ui-level-1")
+        .add_branch::<UiInfoboxAnimalsState>("Ui-level-2")
+        .add_branch::<UiInfoboxCatsContentState>("Ui-level-3");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_plugin(HeadlessUiPlugin {
+            width: 800.0,
+            height: 600.0,
+        })
+        .add_plugin(LocalePlugin)
+        .add_plugin(InputDevicePlugin)
+        .add_plugin(FactsPlugin)
+        .add_plugin(GalleryPlugin)
+        .insert_resource(FrameCounter(0))
+        .insert_resource(ui_schedule)
+        .add_system(run_ui_schedule.exclusive_system());
+    app.world
+        .spawn()
+        .insert(UiInfoboxRoot)
+        .insert(PanelGeometry::new(
+            Vec2::new(20.0, 10.0),
+            Vec2::new(600.0, 400.0),
+        ))
+        .insert(state);
+
+    // Assets load in the background, so wait for the font and then for the picture to settle
+    let mut image = render_ui(&mut app.world);
+    let mut stable_frames = 0;
+    for _ in 0..500 {
+        thread::sleep(Duration::from_millis(10));
+        app.update();
+        let next = render_ui(&mut app.world);
+        let font_loaded = app
+            .world
+            .resource::<AssetServer>()
+            .get_load_state("font.ttf")
+            == LoadState::Loaded;
+        if font_loaded && next == image {
+            stable_frames += 1;
+            if stable_frames == 10 {
+                return image;
+            }
+        } else {
+            stable_frames = 0;
+        }
+        image = next;
+    }
+    panic!("ui didn't settle");
+}
+
+#[test]
+fn infobox_cats_kittens() {
+    assert_screenshot(
+        "infobox_cats_kittens",
+        &screenshot(UiInfoboxState::default()),
+        MAX_DIFFERENT,
+    );
+}
+
+#[test]
+fn infobox_cats_facts() {
+    let state = UiInfoboxState {
+        animal_cats_state: UiInfoboxCatsContentState::Facts,
+        ..default()
+    };
+    assert_screenshot("infobox_cats_facts", &screenshot(state), MAX_DIFFERENT);
+}
+
+#[test]
+fn infobox_dogs() {
+    let state = UiInfoboxState {
+        animal_state: UiInfoboxAnimalsState::Dogs,
+        ..default()
+    };
+    assert_screenshot("infobox_dogs", &screenshot(state), MAX_DIFFERENT);
+}
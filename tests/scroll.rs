@@ -0,0 +1,119 @@
+//! Pressing scrollable content only drags it once the mouse moved, letting go before is a click
+
+use bevy::{
+    asset::AssetPlugin,
+    input::{
+        mouse::{MouseButtonInput, MouseMotion},
+        ButtonState,
+    },
+    math::DVec2,
+    prelude::*,
+};
+use bevy_ui_pattern_example::{
+    builder::node,
+    screenshot::HeadlessUiPlugin,
+    scroll::{ScrollPlugin, ScrollPositions, Scrollable},
+};
+
+/// A 200 x 100 scrollable in the bottom left corner, holding 300 pixels of content
+fn app() -> (App, Entity) {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_plugin(HeadlessUiPlugin {
+            width: 400.0,
+            height: 400.0,
+        })
+        .add_plugin(ScrollPlugin);
+
+    let mut content = None;
+    app.world
+        .spawn()
+        .insert_bundle(node(
+            Color::GREEN,
+            Style {
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::ColumnReverse,
+                size: Size::new(Val::Px(200.0), Val::Px(100.0)),
+                overflow: Overflow::Hidden,
+                ..default()
+            },
+        ))
+        .insert(Scrollable { branch: "list" })
+        .with_children(|parent| {
+            let style = Style {
+                flex_shrink: 0.0,
+                size: Size::new(Val::Px(200.0), Val::Px(300.0)),
+                ..default()
+            };
+            content = Some(parent.spawn_bundle(node(Color::BLUE, style)).id());
+        });
+
+    let mut windows = app.world.resource_mut::<Windows>();
+    let window = windows.get_primary_mut().unwrap();
+    // Cursor positions are y-up, like the ui
+    let cursor = DVec2::new(100.0, 50.0) * window.scale_factor();
+    window.update_cursor_physical_position_from_backend(Some(cursor));
+
+    // Lay out the content before grabbing it
+    app.update();
+    app.update();
+    (app, content.unwrap())
+}
+
+fn left_button(app: &mut App, state: ButtonState) {
+    app.world.send_event(MouseButtonInput {
+        button: MouseButton::Left,
+        state,
+    });
+    app.update();
+}
+
+fn scroll(app: &App) -> &ScrollPositions {
+    app.world.resource::<ScrollPositions>()
+}
+
+fn top(app: &App, content: Entity) -> Val {
+    app.world.get::<Style>(content).unwrap().position.top
+}
+
+#[test]
+fn letting_go_without_moving_clicks_the_content() {
+    let (mut app, content) = app();
+
+    left_button(&mut app, ButtonState::Pressed);
+    assert!(scroll(&app).is_grabbed());
+    assert!(!scroll(&app).is_dragging());
+    assert!(!scroll(&app).was_clicked());
+
+    left_button(&mut app, ButtonState::Released);
+    assert!(!scroll(&app).is_grabbed());
+    assert!(scroll(&app).was_clicked());
+    assert_eq!(top(&app, content), Val::Px(0.0));
+
+    app.update();
+    assert!(!scroll(&app).was_clicked());
+}
+
+#[test]
+fn moving_past_the_threshold_drags_the_content() {
+    let (mut app, content) = app();
+
+    left_button(&mut app, ButtonState::Pressed);
+    app.world.send_event(MouseMotion {
+        delta: Vec2::new(0.0, -2.0),
+    });
+    app.update();
+    assert!(!scroll(&app).is_dragging());
+
+    app.world.send_event(MouseMotion {
+        delta: Vec2::new(0.0, -20.0),
+    });
+    app.update();
+    assert!(scroll(&app).is_dragging());
+    assert_eq!(top(&app, content), Val::Px(-20.0));
+
+    left_button(&mut app, ButtonState::Released);
+    assert!(!scroll(&app).is_grabbed());
+    assert!(!scroll(&app).was_clicked());
+}
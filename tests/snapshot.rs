@@ -0,0 +1,73 @@
+//! Snapshots of the infobox in each of its states, see `tests/snapshots`
+
+use bevy::{asset::AssetPlugin, prelude::*};
+use bevy_ui_pattern_example::{
+    branch::{run_ui_schedule, UiSchedule},
+    infobox::{
+        FrameCounter, UiInfoboxAnimalsState, UiInfoboxCatsContentState, UiInfoboxPanel,
+        UiInfoboxRoot, UiInfoboxState,
+    },
+    panel::PanelGeometry,
+    snapshot::{assert_snapshot, snapshot},
+};
+
+/// Builds an infobox in `state`. The staged approach builds the whole tree in one update.
+fn infobox(state: UiInfoboxState) -> String {
+    let mut ui_schedule = UiSchedule::default();
+    ui_schedule
+        .add_branch::<UiInfoboxPanel>("Ui-level-1")
+        .add_branch::<UiInfoboxAnimalsState>("Ui-level-2")
+        .add_branch::<UiInfoboxCatsContentState>("Ui-level-3");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .insert_resource(FrameCounter(0))
+        .insert_resource(ui_schedule)
+        .add_system(run_ui_schedule.exclusive_system());
+    let root = app
+        .world
+        .spawn()
+        .insert(UiInfoboxRoot)
+        .insert(PanelGeometry::new(
+            Vec2::new(20.0, 10.0),
+            Vec2::new(600.0, 400.0),
+        ))
+        .insert(state)
+        .id();
+    app.update();
+
+    snapshot(&app.world, root)
+}
+
+#[test]
+fn infobox_cats_kittens() {
+    assert_snapshot("infobox_cats_kittens", &infobox(UiInfoboxState::default()));
+}
+
+#[test]
+fn infobox_cats_facts() {
+    let state = UiInfoboxState {
+        animal_cats_state: UiInfoboxCatsContentState::Facts,
+        ..default()
+    };
+    assert_snapshot("infobox_cats_facts", &infobox(state));
+}
+
+#[test]
+fn infobox_dogs() {
+    let state = UiInfoboxState {
+        animal_state: UiInfoboxAnimalsState::Dogs,
+        ..default()
+    };
+    assert_snapshot("infobox_dogs", &infobox(state));
+}
+
+#[test]
+fn infobox_hidden() {
+    let state = UiInfoboxState {
+        visibility: false,
+        ..default()
+    };
+    assert_snapshot("infobox_hidden", &infobox(state));
+}
@@ -0,0 +1,149 @@
+//! `UiSchedule::until_settled` fills branches nested deeper than the stages within one update,
+//! and gives up with a warning once the pass cap is hit
+
+use std::sync::{Arc, Mutex};
+
+use bevy::{
+    ecs::system::{EntityCommands, SystemParamItem},
+    prelude::*,
+    utils::tracing::{
+        self,
+        field::{Field, Visit},
+        span, subscriber, Event, Metadata, Subscriber,
+    },
+};
+use bevy_ui_pattern_example::branch::{AddUiSchedule, UiBranch, UiSchedule};
+
+/// Slot filled with another slot, down to `NestedState::depth` levels
+#[derive(Component)]
+struct NestedSlot;
+
+#[derive(Component)]
+struct NestedState {
+    /// Levels of slots to build, `None` nests forever
+    depth: Option<usize>,
+}
+
+/// Depth of a slot, the root is at 0
+#[derive(Component)]
+struct SlotLevel(usize);
+
+struct Nested;
+
+impl UiBranch for Nested {
+    type Slot = NestedSlot;
+    type State = NestedState;
+    type Context = Query<'static, 'static, &'static SlotLevel>;
+
+    fn build(
+        entity: &mut EntityCommands,
+        state: &NestedState,
+        levels: &mut SystemParamItem<Self::Context>,
+    ) {
+        let level = levels.get(entity.id()).map_or(0, |level| level.0);
+        if state.depth.is_none_or(|depth| level + 1 < depth) {
+            entity.with_children(|parent| {
+                parent
+                    .spawn()
+                    .insert(NestedSlot)
+                    .insert(SlotLevel(level + 1));
+            });
+        }
+    }
+}
+
+fn app(depth: Option<usize>, max_passes: usize) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    let mut ui_schedule = UiSchedule::default();
+    ui_schedule
+        .add_branch::<Nested>("Ui-level-1")
+        .until_settled(max_passes);
+    app.add_ui_schedule(ui_schedule);
+    app.world
+        .spawn()
+        .insert(NestedSlot)
+        .insert(SlotLevel(0))
+        .insert(NestedState { depth });
+    app
+}
+
+fn slots(app: &mut App) -> usize {
+    app.world
+        .query_filtered::<(), With<NestedSlot>>()
+        .iter(&app.world)
+        .count()
+}
+
+/// Records the messages of warnings, for the thread it is the default of
+#[derive(Clone, Default)]
+struct Warnings(Arc<Mutex<Vec<String>>>);
+
+struct Message<'a>(&'a mut String);
+
+impl Visit for Message<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            *self.0 = format!("{:?}", value);
+        }
+    }
+}
+
+impl Subscriber for Warnings {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        *metadata.level() <= tracing::Level::WARN
+    }
+
+    fn new_span(&self, _span: &span::Attributes) -> span::Id {
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _span: &span::Id, _values: &span::Record) {}
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, event: &Event) {
+        let mut message = String::new();
+        event.record(&mut Message(&mut message));
+        self.0.lock().unwrap().push(message);
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+}
+
+#[test]
+fn until_settled_fills_branches_deeper_than_the_stages() {
+    let mut app = app(Some(6), 16);
+    let warnings = Warnings::default();
+    subscriber::with_default(warnings.clone(), || app.update());
+
+    assert_eq!(slots(&mut app), 6);
+    assert!(warnings.0.lock().unwrap().is_empty());
+}
+
+#[test]
+fn until_settled_doesnt_count_the_confirming_pass() {
+    // Every pass builds a level, the last one is confirmed by a pass beyond the cap
+    let mut app = app(Some(4), 4);
+    let warnings = Warnings::default();
+    subscriber::with_default(warnings.clone(), || app.update());
+
+    assert_eq!(slots(&mut app), 4);
+    assert!(warnings.0.lock().unwrap().is_empty());
+}
+
+#[test]
+fn until_settled_warns_at_the_pass_cap() {
+    let mut app = app(None, 4);
+    let warnings = Warnings::default();
+    subscriber::with_default(warnings.clone(), || app.update());
+
+    // The root and a level per pass, including the one which was meant to confirm
+    assert_eq!(slots(&mut app), 6);
+    assert_eq!(
+        *warnings.0.lock().unwrap(),
+        ["Ui schedule didn't settle within 4 passes"]
+    );
+}